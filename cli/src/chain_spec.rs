@@ -355,9 +355,15 @@ pub fn testnet_genesis(
                 400 * 10u128.pow(18),
                 10 * 10u128.pow(18),
             ],
+            eth_tokens: vec![],
+            guardian: None,
         }),
         dao: None,
-        token: Some(TokenConfig { tokens }),
+        token: Some(TokenConfig {
+            tokens,
+            max_supplies: vec![],
+            balances: vec![],
+        }),
     }
 }
 
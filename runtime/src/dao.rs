@@ -19,7 +19,7 @@ use frame_support::{
 use num_traits::ops::checked::CheckedSub;
 use sp_runtime::traits::{Hash, Zero};
 use sp_std::prelude::Vec;
-use system::ensure_signed;
+use system::{ensure_root, ensure_signed};
 
 use crate::types::*;
 use crate::{marketplace, price_oracle, token};
@@ -27,6 +27,7 @@ use crate::{marketplace, price_oracle, token};
 const LOCK_NAME: LockIdentifier = *b"dao_lock";
 const MINIMUM_VOTE_TIOMEOUT: u32 = 30; // ~5 min
 const MAXIMUM_VOTE_TIMEOUT: u32 = 3 * 30 * 24 * 60 * 6; // ~90 days
+const MAXIMUM_DEADLINE_SHIFT: u32 = 10; // blocks searched for a free deadline
 
 pub trait Trait:
     marketplace::Trait
@@ -129,16 +130,14 @@ decl_module! {
 
             let proposal_hash = ("propose_to_add_member", &candidate, dao_id)
                 .using_encoded(<T as system::Trait>::Hashing::hash);
-            let voting_deadline = <system::Module<T>>::block_number() + <DaoTimeouts<T>>::get(dao_id);
-            let mut open_proposals = Self::open_dao_proposals(voting_deadline);
 
             ensure!(<Daos<T>>::contains_key(dao_id), "This DAO not exists");
             ensure!(!<DaoMembers<T>>::contains_key((dao_id, candidate.clone())), "You already are a member of this DAO");
             ensure!(!<DaoAddresses<T>>::contains_key(candidate.clone()), "A DAO can not be a member of other DAO");
             ensure!(<MembersCount>::get(dao_id) < Self::dao_maximum_number_of_members(dao_id), "Maximum number of members for this DAO is reached");
             ensure!(!<OpenDaoProposalsHashes<T>>::contains_key(proposal_hash), "This proposal already open");
-            let len = open_proposals.len() as u32;
-            ensure!(len < Self::open_proposals_per_block(), "Maximum number of open proposals is reached for the target block, try later");
+            let voting_deadline = Self::free_voting_deadline(dao_id)?;
+            let mut open_proposals = Self::open_dao_proposals(voting_deadline);
 
             let dao_proposals_count = <DaoProposalsCount>::get(dao_id);
             let new_dao_proposals_count = dao_proposals_count
@@ -174,15 +173,13 @@ decl_module! {
 
             let proposal_hash = ("propose_to_remove_member", &candidate, dao_id)
                 .using_encoded(<T as system::Trait>::Hashing::hash);
-            let voting_deadline = <system::Module<T>>::block_number() + <DaoTimeouts<T>>::get(dao_id);
-            let mut open_proposals = Self::open_dao_proposals(voting_deadline);
 
             ensure!(<Daos<T>>::contains_key(dao_id), "This DAO not exists");
             ensure!(<DaoMembers<T>>::contains_key((dao_id, candidate.clone())), "You already are not a member of this DAO");
             ensure!(<MembersCount>::get(dao_id) > 1, "You are the last member of this DAO");
             ensure!(!<OpenDaoProposalsHashes<T>>::contains_key(proposal_hash), "This proposal already open");
-            let len = open_proposals.len() as u32;
-            ensure!(len < Self::open_proposals_per_block(), "Maximum number of open proposals is reached for the target block, try later");
+            let voting_deadline = Self::free_voting_deadline(dao_id)?;
+            let mut open_proposals = Self::open_dao_proposals(voting_deadline);
 
             let dao_proposals_count = <DaoProposalsCount>::get(dao_id);
             let new_dao_proposals_count = dao_proposals_count
@@ -218,15 +215,13 @@ decl_module! {
 
             let proposal_hash = ("propose_to_get_loan", &proposer, dao_id, token_id)
                 .using_encoded(<T as system::Trait>::Hashing::hash);
-            let voting_deadline = <system::Module<T>>::block_number() + <DaoTimeouts<T>>::get(dao_id);
-            let mut open_proposals = Self::open_dao_proposals(voting_deadline);
 
             Self::validate_description(&description)?;
             ensure!(<Daos<T>>::contains_key(dao_id), "This DAO not exists");
             ensure!(<DaoMembers<T>>::contains_key((dao_id, proposer.clone())), "You already are not a member of this DAO");
             ensure!(!<OpenDaoProposalsHashes<T>>::contains_key(proposal_hash), "This proposal already open");
-            let len = open_proposals.len() as u32;
-            ensure!(len < Self::open_proposals_per_block(), "Maximum number of open proposals is reached for the target block, try later");
+            let voting_deadline = Self::free_voting_deadline(dao_id)?;
+            let mut open_proposals = Self::open_dao_proposals(voting_deadline);
             Self::withdraw_from_dao_balance_is_valid(dao_id, value)?;
 
             let dao_proposals_count = <DaoProposalsCount>::get(dao_id);
@@ -263,16 +258,14 @@ decl_module! {
 
             let proposal_hash = ("propose_to_change_vote_timeout", &proposer, dao_id)
                 .using_encoded(<T as system::Trait>::Hashing::hash);
-            let voting_deadline = <system::Module<T>>::block_number() + <DaoTimeouts<T>>::get(dao_id);
-            let mut open_proposals = Self::open_dao_proposals(voting_deadline);
 
             Self::validate_vote_timeout(value)?;
             ensure!(<Daos<T>>::contains_key(dao_id), "This DAO not exists");
             ensure!(<DaoMembers<T>>::contains_key((dao_id, proposer.clone())), "You are not a member of this DAO");
             ensure!(!<OpenDaoProposalsHashes<T>>::contains_key(proposal_hash), "This proposal already open");
             ensure!(<DaoTimeouts<T>>::get(dao_id) != value, "new vote timeout equal current vote timeout");
-            let len = open_proposals.len() as u32;
-            ensure!(len < Self::open_proposals_per_block(), "Maximum number of open proposals is reached for the target block, try later");
+            let voting_deadline = Self::free_voting_deadline(dao_id)?;
+            let mut open_proposals = Self::open_dao_proposals(voting_deadline);
 
             let dao_proposals_count = <DaoProposalsCount>::get(dao_id);
             let new_dao_proposals_count = dao_proposals_count
@@ -307,8 +300,6 @@ decl_module! {
 
             let proposal_hash = ("propose_to_change_maximum_number_of_members", &proposer, dao_id)
                 .using_encoded(<T as system::Trait>::Hashing::hash);
-            let voting_deadline = <system::Module<T>>::block_number() + <DaoTimeouts<T>>::get(dao_id);
-            let mut open_proposals = Self::open_dao_proposals(voting_deadline);
 
             Self::validate_number_of_members(value)?;
             ensure!(<Daos<T>>::contains_key(dao_id), "This DAO not exists");
@@ -316,8 +307,8 @@ decl_module! {
             ensure!(!<OpenDaoProposalsHashes<T>>::contains_key(proposal_hash), "This proposal already open");
             ensure!(Self::dao_maximum_number_of_members(dao_id) != value, "New maximum number of members equal current number of members");
             ensure!(Self::members_count(dao_id) <= value, "The current number of members in this DAO more than the new maximum number of members");
-            let len = open_proposals.len() as u32;
-            ensure!(len < Self::open_proposals_per_block(), "Maximum number of open proposals is reached for the target block, try later");
+            let voting_deadline = Self::free_voting_deadline(dao_id)?;
+            let mut open_proposals = Self::open_dao_proposals(voting_deadline);
 
             let dao_proposals_count = <DaoProposalsCount>::get(dao_id);
             let new_dao_proposals_count = dao_proposals_count
@@ -417,6 +408,17 @@ decl_module! {
             Ok(())
         }
 
+        // number of proposals which may share the same voting deadline block
+        #[weight = SimpleDispatchInfo::FixedOperational(10_000)]
+        pub fn set_open_proposals_limit(origin, limit: u32) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(limit > 0, "Open proposals limit must be positive");
+
+            <OpenDaoProposalsLimit>::put(limit);
+            Self::deposit_event(RawEvent::OpenProposalsLimitChanged(limit));
+            Ok(())
+        }
+
         fn on_finalize() {
             let block_number = <system::Module<T>>::block_number();
             Self::open_dao_proposals(block_number)
@@ -455,10 +457,26 @@ decl_event!(
         ProposeToGetLoan(DaoId, AccountId, Days, Rate, Balance, BlockNumber),
         ProposeToChangeTimeout(DaoId, BlockNumber),
         ProposeToChangeMaximumNumberOfMembers(DaoId, MemberId),
+        OpenProposalsLimitChanged(u32),
     }
 );
 
 impl<T: Trait> Module<T> {
+    /// proposal rolls to the next deadline block if the target one is full
+    fn free_voting_deadline(dao_id: DaoId) -> Result<T::BlockNumber, DispatchError> {
+        let mut deadline = <system::Module<T>>::block_number() + <DaoTimeouts<T>>::get(dao_id);
+        for _ in 0..MAXIMUM_DEADLINE_SHIFT {
+            let len = Self::open_dao_proposals(deadline).len() as u32;
+            if len < Self::open_proposals_per_block() {
+                return Ok(deadline);
+            }
+            deadline += T::BlockNumber::from(1);
+        }
+        Err(DispatchError::Other(
+            "Maximum number of open proposals is reached for the next blocks, try later",
+        ))
+    }
+
     fn validate_name(name: &[u8]) -> DispatchResult {
         if name.len() < 10 {
             return Err(DispatchError::Other("The name is very short"));
@@ -696,7 +714,7 @@ impl<T: Trait> Module<T> {
 mod tests {
     use super::*;
 
-    use crate::bridge;
+    use crate::{bridge, nft};
     use frame_support::{
         assert_noop, assert_ok, impl_outer_dispatch, impl_outer_origin, parameter_types,
         traits::{Get, ReservableCurrency},
@@ -704,7 +722,7 @@ mod tests {
     };
     use sp_core::{H160, H256};
     use sp_runtime::{
-        testing::{Header, TestXt},
+        testing::{Header, TestSignature, TestXt, UintAuthorityId},
         traits::{BlakeTwo256, IdentityLookup},
         Perbill,
     };
@@ -790,9 +808,20 @@ mod tests {
     }
     impl token::Trait for Test {
         type Event = ();
+        type OnTransfer = ();
+        type Signature = TestSignature;
+        type Signer = UintAuthorityId;
+    }
+    impl nft::Trait for Test {
+        type Event = ();
     }
     impl bridge::Trait for Test {
         type Event = ();
+        type MessageHandler = ();
+        type ValidatorSignature = TestSignature;
+        type ValidatorSigner = UintAuthorityId;
+        type GuestChain = bridge::Ethereum;
+        type OnBridgeTransfer = ();
     }
 
     pub type Extrinsic = TestXt<Call, ()>;
@@ -903,6 +932,9 @@ mod tests {
                     400 * 10u128.pow(18),
                     10 * 10u128.pow(18),
                 ],
+                // the bridge burns the default token on withdrawals
+                eth_tokens: vec![(H160::zero(), TOKEN_ID)],
+                guardian: None,
             }
             .assimilate_storage(&mut storage);
 
@@ -1232,7 +1264,7 @@ mod tests {
     }
 
     #[test]
-    fn propose_to_add_member_case_maximum_number_of_open_proposals_is_reached() {
+    fn propose_to_add_member_case_target_block_is_full() {
         ExtBuilder::default().build().execute_with(|| {
             assert_eq!(DaoModule::daos_count(), 0);
             assert_ok!(DaoModule::create(
@@ -1251,10 +1283,47 @@ mod tests {
                 Origin::signed(USER3),
                 DAO_ID
             ));
+            assert_ok!(DaoModule::propose_to_add_member(
+                Origin::signed(USER4),
+                DAO_ID
+            ));
+            let deadline = DaoModule::dao_proposals((DAO_ID, 0)).voting_deadline;
+            assert_eq!(DaoModule::dao_proposals((DAO_ID, 2)).voting_deadline, deadline + 1);
+            assert_eq!(DaoModule::open_dao_proposals(deadline + 1), vec![2]);
+
+            for shift in 0..MAXIMUM_DEADLINE_SHIFT {
+                <OpenDaoProposals<Test>>::insert(deadline + u64::from(shift), vec![0, 1]);
+            }
+            assert_noop!(
+                DaoModule::propose_to_add_member(Origin::signed(USER5), DAO_ID),
+                "Maximum number of open proposals is reached for the next blocks, try later"
+            );
+        })
+    }
+
+    #[test]
+    fn set_open_proposals_limit_should_work() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(DaoModule::create(
+                Origin::signed(USER),
+                DAO,
+                DAO_NAME.to_vec(),
+                DAO_DESC.to_vec()
+            ));
             assert_noop!(
-                DaoModule::propose_to_add_member(Origin::signed(USER4), DAO_ID),
-                "Maximum number of open proposals is reached for the target block, try later"
+                DaoModule::set_open_proposals_limit(Origin::signed(USER), 3),
+                DispatchError::BadOrigin
             );
+            assert_noop!(
+                DaoModule::set_open_proposals_limit(Origin::ROOT, 0),
+                "Open proposals limit must be positive"
+            );
+            assert_ok!(DaoModule::set_open_proposals_limit(Origin::ROOT, 3));
+            assert_eq!(DaoModule::open_proposals_per_block(), 3);
+
+            for user in [USER2, USER3, USER4].iter() {
+                assert_ok!(DaoModule::propose_to_add_member(Origin::signed(*user), DAO_ID));
+            }
         })
     }
 
@@ -1352,7 +1421,7 @@ mod tests {
     }
 
     #[test]
-    fn propose_to_remove_member_case_maximum_number_of_open_proposals_is_reached() {
+    fn propose_to_remove_member_case_target_block_is_full() {
         ExtBuilder::default().build().execute_with(|| {
             assert_eq!(DaoModule::daos_count(), 0);
             assert_ok!(DaoModule::create(
@@ -1374,15 +1443,17 @@ mod tests {
                 Origin::signed(USER3),
                 DAO_ID
             ));
-            assert_noop!(
-                DaoModule::propose_to_remove_member(Origin::signed(USER4), DAO_ID),
-                "Maximum number of open proposals is reached for the target block, try later"
-            );
+            assert_ok!(DaoModule::propose_to_remove_member(
+                Origin::signed(USER4),
+                DAO_ID
+            ));
+            let deadline = DaoModule::dao_proposals((DAO_ID, 0)).voting_deadline;
+            assert_eq!(DaoModule::dao_proposals((DAO_ID, 2)).voting_deadline, deadline + 1);
         })
     }
 
     #[test]
-    fn propose_to_get_loan_case_maximum_number_of_open_proposals_is_reached() {
+    fn propose_to_get_loan_case_target_block_is_full() {
         ExtBuilder::default().build().execute_with(|| {
             assert_eq!(DaoModule::daos_count(), 0);
             assert_ok!(DaoModule::create(
@@ -1413,18 +1484,17 @@ mod tests {
                 TOKEN_ID,
                 AMOUNT2
             ));
-            assert_noop!(
-                DaoModule::propose_to_get_loan(
-                    Origin::signed(USER3),
-                    DAO_ID,
-                    PROPOSAL_DESC.to_vec(),
-                    DAYS,
-                    RATE,
-                    TOKEN_ID,
-                    AMOUNT2
-                ),
-                "Maximum number of open proposals is reached for the target block, try later"
-            );
+            assert_ok!(DaoModule::propose_to_get_loan(
+                Origin::signed(USER3),
+                DAO_ID,
+                PROPOSAL_DESC.to_vec(),
+                DAYS,
+                RATE,
+                TOKEN_ID,
+                AMOUNT2
+            ));
+            let deadline = DaoModule::dao_proposals((DAO_ID, 0)).voting_deadline;
+            assert_eq!(DaoModule::dao_proposals((DAO_ID, 2)).voting_deadline, deadline + 1);
         })
     }
 
@@ -2434,7 +2504,7 @@ mod tests {
             assert_eq!(message.status, Status::Withdraw);
 
             // approval
-            assert_eq!(TokenModule::locked((0, USER2)), 0);
+            assert_eq!(TokenModule::locked((0, USER2)), token_amount);
             assert_ok!(BridgeModule::approve_transfer(
                 Origin::signed(V1),
                 sub_message_id
@@ -2461,13 +2531,13 @@ mod tests {
             message = get_message();
             let transfer = BridgeModule::transfers(1);
             assert_eq!(message.status, Status::Confirmed);
-            assert_eq!(transfer.open, true);
+            assert_eq!(transfer.status, ProposalStatus::Pending);
             assert_ok!(BridgeModule::confirm_transfer(
                 Origin::signed(V1),
                 sub_message_id
             ));
             // assert_ok!(BridgeModule::confirm_transfer(Origin::signed(USER1), sub_message_id));
-            //BurnedMessage(Hash, AccountId, H160, u64) event emitted
+            //BurnedMessage(message_id, transfer_id, token, from, to, amount) event emitted
             let tokens_left = amount1 - token_amount;
             assert_eq!(TokenModule::balance_of((TOKEN_ID, USER2)), tokens_left);
             assert_eq!(TokenModule::total_supply(TOKEN_ID), tokens_left);
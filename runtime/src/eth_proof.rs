@@ -0,0 +1,398 @@
+/// Verification of Ethereum headers and receipt proofs.
+/// Headers are imported backwards from a checkpoint approved by bridge validators, each one by the parent hash
+/// of an imported child. The proof is a list of RLP encoded Merkle-Patricia trie nodes from the receipts root to the receipt.
+use crate::types::EthAddress;
+use frame_support::ensure;
+use sp_core::H256;
+use sp_io::hashing::keccak_256;
+use sp_std::prelude::Vec;
+
+type Result<T> = core::result::Result<T, &'static str>;
+
+/// keccak256("RelayMessage(bytes32,address,bytes32,address,uint256)")
+pub fn relay_message_topic() -> H256 {
+    H256::from(keccak_256(b"RelayMessage(bytes32,address,bytes32,address,uint256)"))
+}
+
+/// deposit emitted by the ethereum bridge contract
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Deposit {
+    pub message_id: H256,
+    pub sender: EthAddress,
+    pub recipient: [u8; 32],
+    pub token: EthAddress,
+    pub amount: u128,
+}
+
+/// fields of the ethereum header needed by the bridge
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Header {
+    pub hash: H256,
+    pub parent_hash: H256,
+    pub number: u64,
+    pub receipts_root: H256,
+}
+
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Log {
+    pub address: EthAddress,
+    pub topics: Vec<H256>,
+    pub data: Vec<u8>,
+}
+
+struct Item<'a> {
+    raw: &'a [u8],
+    payload: &'a [u8],
+    is_list: bool,
+}
+
+fn decode_length(data: &[u8], size: usize) -> Result<usize> {
+    ensure!(size <= 4 && size <= data.len(), "Invalid RLP length");
+    Ok(data[..size]
+        .iter()
+        .fold(0usize, |len, b| (len << 8) | *b as usize))
+}
+
+/// split the first RLP item from the rest of the data
+fn decode_item(data: &[u8]) -> Result<(Item, &[u8])> {
+    let prefix = *data.first().ok_or("Unexpected end of RLP data")?;
+    let (is_list, offset, len) = match prefix {
+        0x00..=0x7f => (false, 0, 1),
+        0x80..=0xb7 => (false, 1, (prefix - 0x80) as usize),
+        0xb8..=0xbf => {
+            let size = (prefix - 0xb7) as usize;
+            (false, 1 + size, decode_length(&data[1..], size)?)
+        }
+        0xc0..=0xf7 => (true, 1, (prefix - 0xc0) as usize),
+        _ => {
+            let size = (prefix - 0xf7) as usize;
+            (true, 1 + size, decode_length(&data[1..], size)?)
+        }
+    };
+    let end = offset.checked_add(len).ok_or("Invalid RLP length")?;
+    ensure!(end <= data.len(), "Unexpected end of RLP data");
+    let item = Item {
+        raw: &data[..end],
+        payload: &data[offset..end],
+        is_list,
+    };
+    Ok((item, &data[end..]))
+}
+
+fn decode_list(data: &[u8]) -> Result<Vec<Item>> {
+    let (list, _) = decode_item(data)?;
+    ensure!(list.is_list, "Expected RLP list");
+    let mut items = Vec::new();
+    let mut rest = list.payload;
+    while !rest.is_empty() {
+        let (item, next) = decode_item(rest)?;
+        items.push(item);
+        rest = next;
+    }
+    Ok(items)
+}
+
+pub fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    if bytes.len() == 1 && bytes[0] < 0x80 {
+        return bytes.to_vec();
+    }
+    let mut encoded = encode_length(bytes.len(), 0x80);
+    encoded.extend_from_slice(bytes);
+    encoded
+}
+
+/// list of already encoded items
+pub fn encode_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload: Vec<u8> = items.iter().flat_map(|i| i.iter().cloned()).collect();
+    let mut encoded = encode_length(payload.len(), 0xc0);
+    encoded.extend_from_slice(&payload);
+    encoded
+}
+
+fn encode_length(len: usize, offset: u8) -> Vec<u8> {
+    if len < 56 {
+        return [offset + len as u8].to_vec();
+    }
+    let bytes: Vec<u8> = (len as u64)
+        .to_be_bytes()
+        .iter()
+        .cloned()
+        .skip_while(|b| *b == 0)
+        .collect();
+    let mut encoded = [offset + 55 + bytes.len() as u8].to_vec();
+    encoded.extend_from_slice(&bytes);
+    encoded
+}
+
+/// trie key of the receipt: RLP encoded transaction index
+pub fn encode_index(index: u32) -> Vec<u8> {
+    let bytes: Vec<u8> = index
+        .to_be_bytes()
+        .iter()
+        .cloned()
+        .skip_while(|b| *b == 0)
+        .collect();
+    encode_bytes(&bytes)
+}
+
+fn to_nibbles(key: &[u8]) -> Vec<u8> {
+    key.iter().flat_map(|b| [b >> 4, b & 0x0f].to_vec()).collect()
+}
+
+/// path of the leaf or extension node and whether it is a leaf
+fn decode_node_path(encoded: &[u8]) -> Result<(Vec<u8>, bool)> {
+    let first = *encoded.first().ok_or("Invalid trie node path")?;
+    let flag = first >> 4;
+    let mut nibbles = Vec::new();
+    if flag & 1 == 1 {
+        nibbles.push(first & 0x0f);
+    }
+    nibbles.extend(to_nibbles(&encoded[1..]));
+    Ok((nibbles, flag & 2 == 2))
+}
+
+/// hash of the child node or the child node itself if it is shorter than 32 bytes
+fn child_reference(item: &Item) -> Result<Vec<u8>> {
+    match (item.is_list, item.payload.len()) {
+        (true, _) => Ok(item.raw.to_vec()),
+        (false, 32) => Ok(item.payload.to_vec()),
+        (false, 0) => Err("Key is not in the trie"),
+        _ => Err("Invalid trie node reference"),
+    }
+}
+
+/// value stored under the key in the trie with the given root
+pub fn verify_proof(root: H256, key: &[u8], proof: &[Vec<u8>]) -> Result<Vec<u8>> {
+    let nibbles = to_nibbles(key);
+    let mut path = &nibbles[..];
+    let mut proof = proof.iter();
+    let mut reference = root.as_bytes().to_vec();
+
+    loop {
+        // nodes shorter than 32 bytes are embedded into the parent node
+        let node = if reference.len() == 32 {
+            let node = proof.next().ok_or("Incomplete proof")?;
+            ensure!(keccak_256(node)[..] == reference[..], "Invalid proof node");
+            node.clone()
+        } else {
+            reference
+        };
+        let items = decode_list(&node)?;
+        match items.len() {
+            17 => {
+                if path.is_empty() {
+                    ensure!(!items[16].payload.is_empty(), "Key is not in the trie");
+                    return Ok(items[16].payload.to_vec());
+                }
+                reference = child_reference(&items[path[0] as usize])?;
+                path = &path[1..];
+            }
+            2 => {
+                let (node_path, is_leaf) = decode_node_path(items[0].payload)?;
+                ensure!(path.starts_with(&node_path), "Key is not in the trie");
+                path = &path[node_path.len()..];
+                if is_leaf {
+                    ensure!(path.is_empty(), "Key is not in the trie");
+                    return Ok(items[1].payload.to_vec());
+                }
+                reference = child_reference(&items[1])?;
+            }
+            _ => return Err("Invalid trie node"),
+        }
+    }
+}
+
+/// header [parent, ommers, beneficiary, state, transactions, receipts, bloom, difficulty, number, ...]
+/// later forks only append fields, so the prefix is the same for all of them
+pub fn decode_header(raw: &[u8]) -> Result<Header> {
+    let fields = decode_list(raw)?;
+    ensure!(fields.len() >= 15, "Invalid Ethereum header");
+    ensure!(fields[0].payload.len() == 32, "Invalid Ethereum header parent");
+    ensure!(fields[5].payload.len() == 32, "Invalid Ethereum header receipts root");
+    ensure!(fields[8].payload.len() <= 8, "Invalid Ethereum header number");
+    Ok(Header {
+        hash: H256::from(keccak_256(raw)),
+        parent_hash: H256::from_slice(fields[0].payload),
+        number: fields[8].payload.iter().fold(0u64, |n, b| (n << 8) | *b as u64),
+        receipts_root: H256::from_slice(fields[5].payload),
+    })
+}
+
+/// logs of the receipt [status, cumulative gas, bloom, logs]
+/// typed receipts (EIP-2718) are prefixed with the transaction type byte
+pub fn receipt_logs(receipt: &[u8]) -> Result<Vec<Log>> {
+    let receipt = match receipt.first() {
+        Some(kind) if *kind <= 0x7f => &receipt[1..],
+        _ => receipt,
+    };
+    let fields = decode_list(receipt)?;
+    ensure!(fields.len() == 4, "Invalid receipt");
+    let mut logs = Vec::new();
+    for log in decode_list(fields[3].raw)? {
+        let log = decode_list(log.raw)?;
+        ensure!(log.len() == 3, "Invalid receipt log");
+        ensure!(log[0].payload.len() == 20, "Invalid receipt log address");
+        let mut topics = Vec::new();
+        for topic in decode_list(log[1].raw)? {
+            ensure!(topic.payload.len() == 32, "Invalid receipt log topic");
+            topics.push(H256::from_slice(topic.payload));
+        }
+        logs.push(Log {
+            address: EthAddress::from_slice(log[0].payload),
+            topics,
+            data: log[2].payload.to_vec(),
+        });
+    }
+    Ok(logs)
+}
+
+/// RelayMessage event of the bridge contract by its index among the deposits of the receipt
+pub fn find_deposit(logs: &[Log], contract: EthAddress, index: u32) -> Result<Deposit> {
+    let topic = relay_message_topic();
+    let log = logs
+        .iter()
+        .filter(|l| l.address == contract && l.topics.first() == Some(&topic))
+        .nth(index as usize)
+        .ok_or("Receipt does not contain the bridge deposit")?;
+    ensure!(log.data.len() == 160, "Invalid deposit data");
+
+    let words: Vec<&[u8]> = log.data.chunks(32).collect();
+    ensure!(
+        words[1][..12].iter().all(|b| *b == 0),
+        "Invalid deposit sender"
+    );
+    ensure!(
+        words[3][..12].iter().all(|b| *b == 0),
+        "Invalid deposit token"
+    );
+    ensure!(
+        words[4][..16].iter().all(|b| *b == 0),
+        "Deposit amount overflow"
+    );
+    let mut recipient = [0u8; 32];
+    recipient.copy_from_slice(words[2]);
+    let mut amount = [0u8; 16];
+    amount.copy_from_slice(&words[4][16..]);
+
+    Ok(Deposit {
+        message_id: H256::from_slice(words[0]),
+        sender: EthAddress::from_slice(&words[1][12..]),
+        recipient,
+        token: EthAddress::from_slice(&words[3][12..]),
+        amount: u128::from_be_bytes(amount),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(path: &[u8], value: Vec<u8>) -> Vec<u8> {
+        encode_list(&[encode_bytes(path), encode_bytes(&value)])
+    }
+
+    fn deposit_log(contract: EthAddress, message_id: u8, token: EthAddress, amount: u64) -> Vec<u8> {
+        let mut data = [message_id; 32].to_vec();
+        data.extend_from_slice(&[0u8; 12]);
+        data.extend_from_slice(&[5u8; 20]);
+        data.extend_from_slice(&[6u8; 32]);
+        data.extend_from_slice(&[0u8; 12]);
+        data.extend_from_slice(token.as_bytes());
+        data.extend_from_slice(H256::from_low_u64_be(amount).as_bytes());
+        encode_list(&[
+            encode_bytes(contract.as_bytes()),
+            encode_list(&[encode_bytes(relay_message_topic().as_bytes())]),
+            encode_bytes(&data),
+        ])
+    }
+
+    #[test]
+    fn rlp_encoding_should_work() {
+        assert_eq!(encode_index(0), vec![0x80]);
+        assert_eq!(encode_index(1), vec![0x01]);
+        assert_eq!(encode_index(0x80), vec![0x81, 0x80]);
+        assert_eq!(encode_index(0x1234), vec![0x82, 0x12, 0x34]);
+        let long = [7u8; 60];
+        let encoded = encode_bytes(&long);
+        assert_eq!(&encoded[..2], &[0xb8, 60]);
+        let (item, rest) = decode_item(&encoded).unwrap();
+        assert_eq!(item.payload, &long[..]);
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn verify_proof_should_work() {
+        // key 0x80 goes to the branch child 8, key 0x01 to the child 0
+        let first = leaf(&[0x30], [1u8; 40].to_vec());
+        let second = leaf(&[0x31], [2u8; 40].to_vec());
+        let mut children: Vec<Vec<u8>> = (0..17).map(|_| encode_bytes(&[])).collect();
+        children[8] = encode_bytes(&keccak_256(&first));
+        children[0] = encode_bytes(&keccak_256(&second));
+        let branch = encode_list(&children);
+        let root = H256::from(keccak_256(&branch));
+
+        let proof = vec![branch.clone(), first.clone()];
+        assert_eq!(verify_proof(root, &encode_index(0), &proof), Ok([1u8; 40].to_vec()));
+        let proof = vec![branch.clone(), second];
+        assert_eq!(verify_proof(root, &encode_index(1), &proof), Ok([2u8; 40].to_vec()));
+
+        assert_eq!(
+            verify_proof(root, &encode_index(1), &[branch.clone(), first]),
+            Err("Invalid proof node")
+        );
+        // nibble 2 of the key 0x20 leads to the empty branch child
+        assert_eq!(
+            verify_proof(root, &encode_index(0x20), &[branch]),
+            Err("Key is not in the trie")
+        );
+    }
+
+    #[test]
+    fn find_deposit_should_work() {
+        let contract = EthAddress::from([7u8; 20]);
+        let token = EthAddress::from([8u8; 20]);
+        let other = deposit_log(EthAddress::from([9u8; 20]), 1, token, 50);
+        let logs = encode_list(&[other, deposit_log(contract, 2, token, 99), deposit_log(contract, 3, token, 100)]);
+        // type 2 transaction receipt
+        let mut receipt = [0x02].to_vec();
+        receipt.extend(encode_list(&[
+            encode_bytes(&[1]),
+            encode_bytes(&[0x52, 0x08]),
+            encode_bytes(&[0u8; 256]),
+            logs,
+        ]));
+
+        let logs = receipt_logs(&receipt).unwrap();
+        assert_eq!(logs.len(), 3);
+        let deposit = find_deposit(&logs, contract, 1).unwrap();
+        assert_eq!(deposit.message_id, H256::from([3u8; 32]));
+        assert_eq!(deposit.sender, EthAddress::from([5u8; 20]));
+        assert_eq!(deposit.recipient, [6u8; 32]);
+        assert_eq!(deposit.token, token);
+        assert_eq!(deposit.amount, 100);
+        assert_eq!(find_deposit(&logs, contract, 0).unwrap().message_id, H256::from([2u8; 32]));
+        assert_eq!(
+            find_deposit(&logs, contract, 2),
+            Err("Receipt does not contain the bridge deposit")
+        );
+    }
+
+    #[test]
+    fn decode_header_should_work() {
+        let mut fields: Vec<Vec<u8>> = (0..15).map(|_| encode_bytes(&[])).collect();
+        fields[0] = encode_bytes(&[1u8; 32]);
+        fields[5] = encode_bytes(&[2u8; 32]);
+        fields[8] = encode_bytes(&[0x01, 0x00]);
+        let raw = encode_list(&fields);
+
+        let header = decode_header(&raw).unwrap();
+        assert_eq!(header.hash, H256::from(keccak_256(&raw)));
+        assert_eq!(header.parent_hash, H256::from([1u8; 32]));
+        assert_eq!(header.receipts_root, H256::from([2u8; 32]));
+        assert_eq!(header.number, 256);
+        assert_eq!(decode_header(&encode_list(&fields[..14])), Err("Invalid Ethereum header"));
+    }
+}
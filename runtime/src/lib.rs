@@ -60,7 +60,9 @@ pub use types::*;
 
 pub mod bridge;
 mod dao;
+mod eth_proof;
 mod marketplace;
+mod nft;
 mod token;
 pub use bridge::Call as BridgeCall;
 
@@ -108,7 +110,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     spec_name: create_runtime_str!("akropolisos-node"),
     impl_name: create_runtime_str!("akropolisos-node"),
     authoring_version: 2,
-    spec_version: 2,
+    spec_version: 4,
     impl_version: 2,
     apis: RUNTIME_API_VERSIONS,
 };
@@ -277,7 +279,7 @@ impl pallet_session::Trait for Runtime {
     type ValidatorId = <Self as system::Trait>::AccountId;
     type ValidatorIdOf = pallet_staking::StashOf<Self>;
     type ShouldEndSession = Babe;
-    type SessionManager = Staking;
+    type SessionManager = BridgeSessionManager;
     type SessionHandler = <SessionKeys as OpaqueKeys>::KeyTypeIdProviders;
     type Keys = SessionKeys;
     type DisabledValidatorsThreshold = DisabledValidatorsThreshold;
@@ -670,8 +672,26 @@ impl pallet_vesting::Trait for Runtime {
     type MinVestedTransfer = MinVestedTransfer;
 }
 
+/// notifies the bridge about the validator set of every new session
+pub struct BridgeSessionManager;
+impl pallet_session::SessionManager<AccountId> for BridgeSessionManager {
+    fn new_session(new_index: pallet_session::SessionIndex) -> Option<Vec<AccountId>> {
+        let next = <Staking as pallet_session::SessionManager<AccountId>>::new_session(new_index);
+        Bridge::on_new_session(Session::validators());
+        next
+    }
+    fn end_session(end_index: pallet_session::SessionIndex) {
+        <Staking as pallet_session::SessionManager<AccountId>>::end_session(end_index)
+    }
+}
+
 impl bridge::Trait for Runtime {
     type Event = Event;
+    type MessageHandler = ();
+    type ValidatorSignature = Signature;
+    type ValidatorSigner = <Signature as Verify>::Signer;
+    type GuestChain = bridge::Ethereum;
+    type OnBridgeTransfer = ();
 }
 
 impl dao::Trait for Runtime {
@@ -684,6 +704,13 @@ impl marketplace::Trait for Runtime {
 
 impl token::Trait for Runtime {
     type Event = Event;
+    type OnTransfer = ();
+    type Signature = Signature;
+    type Signer = <Signature as Verify>::Signer;
+}
+
+impl nft::Trait for Runtime {
+    type Event = Event;
 }
 
 /// We need to define the Transaction signer for that using the Key definition
@@ -741,11 +768,13 @@ construct_runtime!(
 		Recovery: pallet_recovery::{Module, Call, Storage, Event<T>},
 		Vesting: pallet_vesting::{Module, Call, Storage, Event<T>, Config<T>},
 		// Akropolis pallets
-		Token: token::{Module, Call, Storage, Config, Event<T>},
-        Bridge: bridge::{Module, Call, Storage, Config<T>, Event<T>},
+		Token: token::{Module, Call, Storage, Config<T>, Event<T>},
+        Bridge: bridge::{Module, Call, Storage, Config<T>, Event<T>, ValidateUnsigned},
 		Dao: dao::{Module, Call, Storage, Config, Event<T>},
 		Marketplace: marketplace::{Module, Call, Storage, Event<T>},
 		PriceOracle: price_oracle::{Module, Call, Storage, Event<T>, ValidateUnsigned},
+		// appended so the indices of the modules above stay the same
+		Nft: nft::{Module, Call, Storage, Event<T>},
 	}
 );
 
@@ -909,6 +938,26 @@ impl_runtime_apis! {
         }
     }
 
+    impl bridge::BridgeApi<Block, AccountId, Hash, BlockNumber> for Runtime {
+        fn pending_proposals() -> Vec<ProposalView<Hash, BlockNumber>> {
+            Bridge::pending_proposals()
+        }
+
+        fn proposal_by_message(message_id: Hash) -> Option<(ProposalId, ProposalStatus)> {
+            Bridge::proposal_by_message(message_id)
+        }
+
+        fn proposal_votes(transfer_id: ProposalId) -> Vec<(AccountId, Option<Status>)> {
+            Bridge::proposal_votes(transfer_id)
+        }
+    }
+
+    impl token::TokenApi<Block, AccountId, Balance> for Runtime {
+        fn account_tokens(account: AccountId) -> Vec<(TokenId, Balance)> {
+            Token::account_tokens(account)
+        }
+    }
+
     impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<
         Block,
         Balance,
@@ -0,0 +1,174 @@
+/// Pallet keeping unique assets bridged from Ethereum
+/// Every asset is identified by the ERC-721 contract (collection) and its token id there,
+/// assets are minted and burned by the bridge and can be transferred on substrate side freely
+///
+use crate::types::{EthAddress, NftId};
+use frame_support::{
+    decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
+    weights::SimpleDispatchInfo, StorageMap,
+};
+use system::{self, ensure_signed};
+
+type Result<T> = core::result::Result<T, &'static str>;
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+    {
+        Minted(EthAddress, NftId, AccountId),
+        Burned(EthAddress, NftId, AccountId),
+        Transferred(EthAddress, NftId, AccountId, AccountId),
+    }
+);
+
+pub trait Trait: system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as NftStorage {
+        pub Owners get(fn owner_of): map hasher(opaque_blake2_256) (EthAddress, NftId) => Option<T::AccountId>;
+        pub OwnedCount get(fn balance_of): map hasher(opaque_blake2_256) T::AccountId => u64;
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn transfer(origin, collection: EthAddress, token_ref: NftId, to: T::AccountId) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::check_owner(collection, token_ref, &sender)?;
+
+            <Owners<T>>::insert((collection, token_ref), to.clone());
+            <OwnedCount<T>>::mutate(&sender, |c| *c -= 1);
+            <OwnedCount<T>>::mutate(&to, |c| *c += 1);
+            Self::deposit_event(RawEvent::Transferred(collection, token_ref, sender, to));
+            Ok(())
+        }
+    }
+}
+
+impl<T: Trait> Module<T> {
+    pub fn mint(collection: EthAddress, token_ref: NftId, owner: T::AccountId) -> Result<()> {
+        ensure!(
+            !<Owners<T>>::contains_key((collection, token_ref)),
+            "This asset already exists"
+        );
+        <Owners<T>>::insert((collection, token_ref), owner.clone());
+        <OwnedCount<T>>::mutate(&owner, |c| *c += 1);
+        Self::deposit_event(RawEvent::Minted(collection, token_ref, owner));
+        Ok(())
+    }
+
+    pub fn burn(collection: EthAddress, token_ref: NftId, owner: T::AccountId) -> Result<()> {
+        Self::check_owner(collection, token_ref, &owner)?;
+        <Owners<T>>::remove((collection, token_ref));
+        <OwnedCount<T>>::mutate(&owner, |c| *c -= 1);
+        Self::deposit_event(RawEvent::Burned(collection, token_ref, owner));
+        Ok(())
+    }
+
+    pub fn check_owner(collection: EthAddress, token_ref: NftId, account: &T::AccountId) -> Result<()> {
+        let owner = Self::owner_of((collection, token_ref)).ok_or("This asset does not exist")?;
+        ensure!(owner == *account, "This asset is owned by another account");
+        Ok(())
+    }
+}
+
+/// tests for this module
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frame_support::{assert_noop, assert_ok, impl_outer_origin, parameter_types, weights::Weight};
+    use sp_core::{H160, H256};
+    use sp_runtime::{
+        testing::Header,
+        traits::{BlakeTwo256, IdentityLookup},
+        Perbill,
+    };
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+        type ModuleToIndex = ();
+        type AccountData = ();
+        type OnNewAccount = ();
+        type OnKilledAccount = ();
+    }
+    impl Trait for Test {
+        type Event = ();
+    }
+
+    type NftModule = Module<Test>;
+
+    const COLLECTION: [u8; 20] = [9u8; 20];
+    const USER1: u64 = 1;
+    const USER2: u64 = 2;
+
+    fn new_test_ext() -> sp_io::TestExternalities {
+        system::GenesisConfig::default()
+            .build_storage::<Test>()
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn mint_transfer_and_burn_should_work() {
+        new_test_ext().execute_with(|| {
+            let collection = H160::from(COLLECTION);
+            let token_ref = NftId::from_low_u64_be(42);
+
+            assert_ok!(NftModule::mint(collection, token_ref, USER1));
+            assert_eq!(NftModule::owner_of((collection, token_ref)), Some(USER1));
+            assert_eq!(NftModule::balance_of(USER1), 1);
+            assert_noop!(
+                NftModule::mint(collection, token_ref, USER2),
+                "This asset already exists"
+            );
+
+            assert_noop!(
+                NftModule::transfer(Origin::signed(USER2), collection, token_ref, USER2),
+                "This asset is owned by another account"
+            );
+            assert_ok!(NftModule::transfer(Origin::signed(USER1), collection, token_ref, USER2));
+            assert_eq!(NftModule::owner_of((collection, token_ref)), Some(USER2));
+            assert_eq!(NftModule::balance_of(USER1), 0);
+
+            assert_ok!(NftModule::burn(collection, token_ref, USER2));
+            assert_eq!(NftModule::owner_of((collection, token_ref)), None);
+            assert_eq!(NftModule::balance_of(USER2), 0);
+            assert_noop!(
+                NftModule::burn(collection, token_ref, USER2),
+                "This asset does not exist"
+            );
+        })
+    }
+}
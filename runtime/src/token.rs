@@ -2,78 +2,325 @@
 /// You can use mint to create tokens or burn created tokens
 /// and transfer tokens on substrate side freely or operate with total_supply
 ///
-use crate::types::{Token, TokenId};
+use crate::types::{
+    BurnReason, Distribution, DistributionId, SnapshotId, Token, TokenId, TokenRole, TransferFee, VestingSchedule,
+};
 use frame_support::{
-    decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
-    weights::SimpleDispatchInfo, StorageMap,
+    decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
+    traits::{Currency, ExistenceRequirement, LockIdentifier, ReservableCurrency}, weights::SimpleDispatchInfo, StorageDoubleMap, StorageMap,
+    StorageValue,
 };
+use codec::Encode;
 use num_traits::ops::checked::{CheckedAdd, CheckedSub};
-use sp_runtime::traits::{StaticLookup, Zero};
+use sp_runtime::{
+    helpers_128bit::multiply_by_rational,
+    traits::{AccountIdConversion, IdentifyAccount, One, SaturatedConversion, Saturating, StaticLookup, Verify, Zero},
+    ModuleId,
+};
 use sp_std::prelude::Vec;
-use system::{self, ensure_signed};
+use system::{self, ensure_root, ensure_signed};
 
 type Result<T> = core::result::Result<T, &'static str>;
+// balance and supply changes fail with the module error, other modules take it as a string with ?
+type TokenResult<R, T> = core::result::Result<R, Error<T>>;
+
+const MAX_NAME_LENGTH: usize = 64;
+const MAX_MEMO_LENGTH: usize = 128;
+const MAX_METADATA_LENGTH: usize = 256;
+const MAX_BATCH_SIZE: usize = 100;
+const MAX_HOLDERS_PAGE: u32 = 100;
+const VESTING_ID: LockIdentifier = *b"vesting ";
+const DISTRIBUTION_MODULE_ID: ModuleId = ModuleId(*b"akro/dst");
+const WRAP_MODULE_ID: ModuleId = ModuleId(*b"akro/wrp");
 
 decl_event!(
     pub enum Event<T>
     where
         AccountId = <T as system::Trait>::AccountId,
         Balance = <T as balances::Trait>::Balance,
+        BlockNumber = <T as system::Trait>::BlockNumber,
     {
         Transfer(AccountId, AccountId, Balance),
-        Approval(AccountId, AccountId, Balance),
+        // token, sender, recipient, amount received, fee
+        TransferFeeCharged(TokenId, AccountId, AccountId, Balance, Balance),
+        // token, fee and its beneficiary
+        TransferFeeChanged(TokenId, Option<(TransferFee<Balance>, AccountId)>),
+        // token, sender, recipient, amount, memo attributing the transfer
+        TransferWithMemo(TokenId, AccountId, AccountId, Balance, Vec<u8>),
+        // token, owner, spender, allowance
+        Approval(TokenId, AccountId, AccountId, Balance),
+        // owner, operator, approved
+        OperatorSet(AccountId, AccountId, bool),
         Mint(AccountId, Balance),
-        Burn(AccountId, Balance),
+        // token, holder, amount, reason and memo of the burn
+        Burn(TokenId, AccountId, Balance, Option<BurnReason>, Option<Vec<u8>>),
+        // token, creator, initial supply
+        TokenCreated(TokenId, AccountId, Balance),
+        TokenDestroyed(TokenId),
+        // token, name, display decimals, URI after the update
+        MetadataUpdated(TokenId, Vec<u8>, u16, Vec<u8>),
+        MetadataFrozen(TokenId),
+        MinimumBalanceChanged(TokenId, Balance),
+        MaxHoldersChanged(TokenId, Option<u32>),
+        // token, account, whether it becomes a holder over the max holders
+        MaxHoldersExemptionChanged(TokenId, AccountId, bool),
+        TokenAdminChanged(TokenId, Option<AccountId>),
+        RoleGranted(TokenId, TokenRole, AccountId),
+        RoleRevoked(TokenId, TokenRole, AccountId),
+        AccountFrozen(TokenId, AccountId),
+        AccountThawed(TokenId, AccountId),
+        TokenPaused(TokenId),
+        TokenUnpaused(TokenId),
+        WhitelistOnlyChanged(TokenId, bool),
+        SoulboundChanged(TokenId, bool),
+        Whitelisted(TokenId, AccountId),
+        RemovedFromWhitelist(TokenId, AccountId),
+        SnapshotCreated(TokenId, SnapshotId, BlockNumber),
+        // distribution, token of the holders, source token, amount, snapshot
+        DistributionCreated(DistributionId, TokenId, TokenId, Balance, SnapshotId),
+        DistributionClaimed(DistributionId, AccountId, Balance),
+        WrappedNativeSet(TokenId),
+        Wrapped(AccountId, Balance),
+        Unwrapped(AccountId, Balance),
+        // token, account, amount locked by the schedule
+        VestingCreated(TokenId, AccountId, Balance),
+        // token, account, amount still locked
+        VestingClaimed(TokenId, AccountId, Balance),
     }
 );
 
+sp_api::decl_runtime_apis! {
+    /// token state queried by wallets
+    pub trait TokenApi<AccountId, Balance>
+    where
+        AccountId: codec::Codec,
+        Balance: codec::Codec,
+    {
+        /// non-zero balances of the account in all tokens
+        fn account_tokens(account: AccountId) -> Vec<(TokenId, Balance)>;
+    }
+}
+
 pub trait Trait: balances::Trait + system::Trait {
     type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    type OnTransfer: OnTokenTransfer<Self::AccountId, Self::Balance>;
+    /// signature of the owner approving the spender off-chain
+    type Signature: frame_support::Parameter + Verify<Signer = Self::Signer>;
+    type Signer: IdentifyAccount<AccountId = Self::AccountId>;
+}
+
+/// called after the balances are changed
+pub trait OnTokenTransfer<AccountId, Balance> {
+    fn on_transfer(token_id: TokenId, from: &AccountId, to: &AccountId, amount: Balance);
+    fn on_mint(token_id: TokenId, to: &AccountId, amount: Balance);
+    fn on_burn(token_id: TokenId, from: &AccountId, amount: Balance);
+}
+
+impl<AccountId, Balance> OnTokenTransfer<AccountId, Balance> for () {
+    fn on_transfer(_token_id: TokenId, _from: &AccountId, _to: &AccountId, _amount: Balance) {}
+    fn on_mint(_token_id: TokenId, _to: &AccountId, _amount: Balance) {}
+    fn on_burn(_token_id: TokenId, _from: &AccountId, _amount: Balance) {}
 }
 
 decl_storage! {
     trait Store for Module<T: Trait> as TokenStorage {
-        pub Count get(fn count) build(|config: &GenesisConfig| {
+        pub Count get(fn count) build(|config: &GenesisConfig<T>| {
             config.tokens.clone().len() as u32
         }): TokenId;
         pub Locked get(fn locked): map hasher(opaque_blake2_256) (TokenId, T::AccountId) => T::Balance;
+        // locks set by other modules, they overlap like the locks of balances
+        pub Locks get(fn locks): map hasher(opaque_blake2_256) (TokenId, T::AccountId) => Vec<(LockIdentifier, T::Balance)>;
+        pub Vesting get(fn vesting): map hasher(opaque_blake2_256) (TokenId, T::AccountId) => Option<VestingSchedule<T::Balance, T::BlockNumber>>;
+        // accounts with allowances, locks or vesting of the token, cleared with the token
+        pub TokenAccounts get(fn token_accounts): map hasher(opaque_blake2_256) TokenId => Vec<T::AccountId>;
+        // last snapshot of the token, snapshots start from 1
+        pub SnapshotCount get(fn snapshot_count): map hasher(opaque_blake2_256) TokenId => SnapshotId;
+        pub SnapshotBlocks get(fn snapshot_block): double_map hasher(opaque_blake2_256) TokenId, hasher(opaque_blake2_256) SnapshotId => Option<T::BlockNumber>;
+        // values before the first change after a snapshot, ordered by the snapshot
+        pub BalanceSnapshots get(fn balance_snapshots): map hasher(opaque_blake2_256) (TokenId, T::AccountId) => Vec<(SnapshotId, T::Balance)>;
+        pub SupplySnapshots get(fn supply_snapshots): map hasher(opaque_blake2_256) TokenId => Vec<(SnapshotId, T::Balance)>;
+        // token minted 1:1 for the native currency locked in the module
+        pub WrappedNative get(fn wrapped_native): Option<TokenId>;
+        pub DistributionCount get(fn distribution_count): DistributionId;
+        pub Distributions get(fn distribution): map hasher(opaque_blake2_256) DistributionId => Option<Distribution<T::Balance>>;
+        pub DistributionClaims get(fn distribution_claimed): double_map hasher(opaque_blake2_256) DistributionId, hasher(opaque_blake2_256) T::AccountId => bool;
+        // tokens moved out of the balance by other modules, still counted in the total supply
+        pub Reserved get(fn reserved_balance): map hasher(opaque_blake2_256) (TokenId, T::AccountId) => T::Balance;
 
-        pub Tokens get(fn tokens) build(|config: &GenesisConfig| {
+        pub Tokens get(fn tokens) build(|config: &GenesisConfig<T>| {
             config.tokens.clone()
         }): Vec<Token>;
-        pub TokenMap get(fn token_map) build(|config: &GenesisConfig| {
+        pub TokenMap get(fn token_map) build(|config: &GenesisConfig<T>| {
             config.tokens.clone().into_iter()
             .map(|t: Token| (t.id as u32, t)).collect::<Vec<_>>()
         }): map hasher(opaque_blake2_256) TokenId => Token;
-        pub TokenIds get(fn token_id_by_symbol) build(|config: &GenesisConfig| {
+        // symbols are unique, both lookups are none for unknown tokens
+        pub TokenIds get(fn token_id_by_symbol) build(|config: &GenesisConfig<T>| {
             config.tokens.clone().into_iter().map(|t: Token| (t.symbol, t.id)).collect::<Vec<_>>()
-        }): map hasher(opaque_blake2_256) Vec<u8> => TokenId;
-        pub TokenSymbol get(fn token_symbol_by_id) build(|config: &GenesisConfig| {
-            config.tokens.clone().into_iter().enumerate()
-            .map(|(i, t): (usize, Token)| (i as u32, t.symbol)).collect::<Vec<_>>()
-        }): map hasher(opaque_blake2_256) TokenId => Vec<u8>;
+        }): map hasher(opaque_blake2_256) Vec<u8> => Option<TokenId>;
+        pub TokenSymbol get(fn symbol_by_token_id) build(|config: &GenesisConfig<T>| {
+            config.tokens.clone().into_iter().map(|t: Token| (t.id, t.symbol)).collect::<Vec<_>>()
+        }): map hasher(opaque_blake2_256) TokenId => Option<Vec<u8>>;
         pub TotalSupply get(fn total_supply): map hasher(opaque_blake2_256) TokenId => T::Balance;
         pub Balance get(fn balance_of): map hasher(opaque_blake2_256) (TokenId, T::AccountId) => T::Balance;
-        pub Allowance get(fn allowance_of): map hasher(opaque_blake2_256) (TokenId, T::AccountId, T::AccountId) => T::Balance;
+        // non-zero balances below it are dust and can't be left on accounts
+        pub MinimumBalance get(fn minimum_balance): map hasher(opaque_blake2_256) TokenId => T::Balance;
+        // accounts with a non-zero balance, enumerated by index
+        pub HolderCount get(fn holder_count): map hasher(opaque_blake2_256) TokenId => u32;
+        pub Holders get(fn holder): double_map hasher(opaque_blake2_256) TokenId, hasher(opaque_blake2_256) u32 => Option<T::AccountId>;
+        // securities-style limit, reached holders keep their balances if it's lowered
+        pub MaxHolders get(fn max_holders): map hasher(opaque_blake2_256) TokenId => Option<u32>;
+        // admin override, exempt accounts become holders even over the limit
+        pub MaxHoldersExempt get(fn is_max_holders_exempt): double_map hasher(opaque_blake2_256) TokenId, hasher(opaque_blake2_256) T::AccountId => bool;
+        HolderIndex: double_map hasher(opaque_blake2_256) TokenId, hasher(opaque_blake2_256) T::AccountId => Option<u32>;
+        // full name of the token, the symbol is kept in the token itself
+        pub TokenNames get(fn token_name): map hasher(opaque_blake2_256) TokenId => Vec<u8>;
+        // URI or IPFS hash of the logo and documents of the token
+        pub TokenMetadata get(fn token_metadata): map hasher(opaque_blake2_256) TokenId => Vec<u8>;
+        // decimals shown by front-ends, the bridge keeps converting amounts with the decimals of the token
+        pub DisplayDecimals get(fn display_decimals_override): map hasher(opaque_blake2_256) TokenId => Option<u16>;
+        // name, decimals and URI can't be changed once frozen, the symbol never can
+        pub MetadataFrozen get(fn is_metadata_frozen): map hasher(opaque_blake2_256) TokenId => bool;
+        // account which created the token and its reserved deposit
+        pub TokenCreators get(fn token_creator): map hasher(opaque_blake2_256) TokenId => Option<(T::AccountId, T::Balance)>;
+        // account managing roles of the token
+        pub TokenAdmins get(fn token_admin): map hasher(opaque_blake2_256) TokenId => Option<T::AccountId>;
+        pub TokenRoles get(fn has_role): double_map hasher(opaque_blake2_256) TokenId, hasher(opaque_blake2_256) (TokenRole, T::AccountId) => bool;
+        // number of accounts holding the role
+        pub RoleHolders get(fn role_holders): map hasher(opaque_blake2_256) (TokenId, TokenRole) => u32;
+        // accounts which can neither send nor receive the token
+        pub FrozenAccounts get(fn is_frozen): double_map hasher(opaque_blake2_256) TokenId, hasher(opaque_blake2_256) T::AccountId => bool;
+        // cap of the total supply, set once at creation
+        pub MaxSupply get(fn max_supply): map hasher(opaque_blake2_256) TokenId => Option<T::Balance>;
+        // fee charged on transfers of the token and the account receiving it
+        pub TransferFees get(fn transfer_fee): map hasher(opaque_blake2_256) TokenId => Option<(TransferFee<T::Balance>, T::AccountId)>;
+        // tokens which can not be transferred until the admin unpauses them
+        pub PausedTokens get(fn is_paused): map hasher(opaque_blake2_256) TokenId => bool;
+        // tokens which are only minted and burned, e.g. reputation or membership
+        pub Soulbound get(fn is_soulbound): map hasher(opaque_blake2_256) TokenId => bool;
+        // compliance mode, the token moves only between whitelisted accounts
+        pub WhitelistOnly get(fn whitelist_only): map hasher(opaque_blake2_256) TokenId => bool;
+        pub Whitelist get(fn is_whitelisted): double_map hasher(opaque_blake2_256) TokenId, hasher(opaque_blake2_256) T::AccountId => bool;
+        // native funds reserved for a token created by an account
+        pub CreationDeposit get(fn creation_deposit): T::Balance;
+        // used once by each permit of the owner
+        pub PermitNonces get(fn permit_nonce): map hasher(opaque_blake2_256) T::AccountId => u32;
+        // amount the spender can transfer from the owner
+        pub Allowances get(fn allowance_of): double_map hasher(opaque_blake2_256) (TokenId, T::AccountId), hasher(opaque_blake2_256) T::AccountId => T::Balance;
+        // operator can move any balance of the owner, e.g. a marketplace or a pool
+        pub Operators get(fn is_operator): double_map hasher(opaque_blake2_256) T::AccountId, hasher(opaque_blake2_256) T::AccountId => bool;
     }
     add_extra_genesis{
         config(tokens): Vec<Token>;
+        config(max_supplies): Vec<(TokenId, T::Balance)>;
+        // pre-distributed tokens, counted in the total supply
+        config(balances): Vec<(TokenId, T::AccountId, T::Balance)>;
+        build(|config: &GenesisConfig<T>| {
+            let mut symbols: Vec<_> = config.tokens.iter().map(|t| &t.symbol).collect();
+            symbols.sort();
+            symbols.dedup();
+            assert!(symbols.len() == config.tokens.len(), "Token symbols should be unique");
+
+            let is_genesis_token = |token_id: &TokenId| config.tokens.iter().any(|t| t.id == *token_id);
+            for (token_id, max) in config.max_supplies.iter() {
+                assert!(is_genesis_token(token_id), "Max supply of an unknown token");
+                <MaxSupply<T>>::insert(token_id, max);
+            }
+            for (token_id, account, amount) in config.balances.iter() {
+                assert!(is_genesis_token(token_id), "Balance of an unknown token");
+                <Module<T>>::_mint(*token_id, account.clone(), *amount).expect("Genesis balances should be mintable");
+            }
+        });
+    }
+}
+
+decl_error! {
+    pub enum Error for Module<T: Trait> {
+        /// Amount should be non-zero
+        ZeroAmount,
+        /// Balance is lower than the amount
+        InsufficientBalance,
+        /// Reserved balance is lower than the amount
+        InsufficientReserved,
+        /// Locked balance is lower than the amount
+        InsufficientLocked,
+        /// Part of the balance is locked or reserved
+        LockedFunds,
+        /// Balance or supply overflow
+        Overflow,
+        /// Balance or supply underflow
+        Underflow,
+        /// The token does not exist
+        TokenNotFound,
+        /// Token max supply exceeded
+        MaxSupplyExceeded,
+        /// Token max holders exceeded
+        MaxHoldersExceeded,
+        /// The balance would be below the token minimum
+        BelowMinimumBalance,
+        /// Transfer amount does not cover the fee
+        FeeNotCovered,
+        /// Transfers of this token are paused
+        TokenPaused,
+        /// The token is not transferable
+        NotTransferable,
+        /// The account is frozen
+        AccountFrozen,
+        /// The sender account is frozen
+        SenderFrozen,
+        /// The recipient account is frozen
+        RecipientFrozen,
+        /// The sender account is not whitelisted
+        SenderNotWhitelisted,
+        /// The recipient account is not whitelisted
+        RecipientNotWhitelisted,
+        /// This account is not a minter of the token
+        NotMinter,
+        /// This account is not a burner of the token
+        NotBurner,
+        /// The memo is too long
+        MemoTooLong,
+    }
+}
+
+// results of the helpers are compared in tests
+impl<T: Trait> PartialEq for Error<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
     }
 }
 
 decl_module! {
     pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+
         fn deposit_event() = default;
 
-        // ( ! ): can be called directly
-        // ( ? ): do we even need this?
+        // holder destroys own tokens, e.g. to redeem them on ethereum outside of the bridge
         #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
-        fn burn(origin, from: T::AccountId, token_id: TokenId, #[compact] amount: T::Balance) -> DispatchResult {
-            ensure_signed(origin)?;
-            let token = <TokenMap>::get(token_id);
-            Self::check_token_exist(&token.symbol)?;
-            Self::_burn(0, from.clone(), amount)?;
-            Self::deposit_event(RawEvent::Burn(from, amount));
+        fn burn(origin, token_id: TokenId, #[compact] amount: T::Balance, memo: Option<Vec<u8>>) -> DispatchResult {
+            let holder = ensure_signed(origin)?;
+            ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+            ensure!(memo.as_ref().map_or(true, |m| m.len() <= MAX_MEMO_LENGTH), Error::<T>::MemoTooLong);
+
+            Self::_burn(token_id, holder.clone(), amount)?;
+            Self::deposit_event(RawEvent::Burn(token_id, holder, amount, Some(BurnReason::Redemption), memo));
+            Ok(())
+        }
+
+        // burner of the token destroys tokens of any account
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn burn_from(
+            origin,
+            from: T::AccountId,
+            token_id: TokenId,
+            #[compact] amount: T::Balance,
+            reason: Option<BurnReason>,
+            memo: Option<Vec<u8>>
+        ) -> DispatchResult {
+            let burner = ensure_signed(origin)?;
+            Self::check_token_exists(token_id)?;
+            Self::burn_as(&burner, token_id, from, amount, reason, memo)?;
             Ok(())
         }
 
@@ -81,337 +328,2283 @@ decl_module! {
         // ( ? ): do we even need this?
         #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
         fn mint(origin, to: T::AccountId, token_id: TokenId, #[compact] amount: T::Balance) -> DispatchResult{
-            ensure_signed(origin)?;
-            let token = <TokenMap>::get(token_id);
-            Self::check_token_exist(&token.symbol)?;
-            Self::_mint(token.id, to.clone(), amount)?;
+            let minter = ensure_signed(origin)?;
+            Self::check_token_exists(token_id)?;
+            Self::mint_as(&minter, token_id, to.clone(), amount)?;
             Self::deposit_event(RawEvent::Mint(to.clone(), amount));
             Ok(())
         }
 
-        // TODO: do we need it from outside?
-        // fn create_token(origin, token: Vec<u8>) -> DispatchResult {
-        //     ensure_signed(origin)?;
-        //     Self::check_token_exist(&token)
-        // }
+        // all mints are checked before the first one is made, e.g. for airdrops
         #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
-        fn transfer(origin,
-            to: <T::Lookup as StaticLookup>::Source,
-            token_id: TokenId,
-            #[compact] amount: T::Balance
-        ) -> DispatchResult{
-            let sender = ensure_signed(origin)?;
-            let to = T::Lookup::lookup(to)?;
-            ensure!(!amount.is_zero(), "Transfer Amount should be non-zero");
+        fn mint_batch(origin, token_id: TokenId, mints: Vec<(T::AccountId, T::Balance)>) -> DispatchResult {
+            let minter = ensure_signed(origin)?;
+            Self::check_token_exists(token_id)?;
+            ensure!(Self::has_role(token_id, (TokenRole::Minter, minter)), Error::<T>::NotMinter);
+            ensure!(!mints.is_empty(), "The batch is empty");
+            ensure!(mints.len() <= MAX_BATCH_SIZE, "The batch is too large");
+
+            let recipients: Vec<&T::AccountId> = mints.iter().map(|(to, _)| to).collect();
+            Self::check_max_holders(token_id, Self::new_holders(token_id, &recipients))?;
+            let mut total = T::Balance::zero();
+            for (to, amount) in mints.iter() {
+                ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+                total = total.checked_add(amount).ok_or("Overflow adding batch amounts")?;
+                let balance = Self::balance_of((token_id, to.clone())).saturating_add(*amount);
+                Self::check_minimum_balance(token_id, balance)?;
+            }
+            // balances can't overflow once the total supply doesn't
+            Self::check_max_supply(token_id, total)?;
 
-            Self::make_transfer(token_id, sender, to, amount)?;
+            for (to, amount) in mints {
+                Self::_mint(token_id, to.clone(), amount)?;
+                Self::deposit_event(RawEvent::Mint(to, amount));
+            }
             Ok(())
         }
 
         #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
-        fn approve(origin,
-            spender: <T::Lookup as StaticLookup>::Source,
-            token_id: TokenId,
-            #[compact] value: T::Balance
-        ) -> DispatchResult{
-            let sender = ensure_signed(origin)?;
-            let spender = T::Lookup::lookup(spender)?;
+        fn mint_vested(origin, to: T::AccountId, token_id: TokenId, schedule: VestingSchedule<T::Balance, T::BlockNumber>) -> DispatchResult {
+            let minter = ensure_signed(origin)?;
+            ensure!(Self::has_role(token_id, (TokenRole::Minter, minter)), Error::<T>::NotMinter);
+            Self::_mint_vested(token_id, to.clone(), schedule)?;
+            Self::deposit_event(RawEvent::Mint(to, schedule.locked));
+            Ok(())
+        }
 
-            <Allowance<T>>::insert((token_id, sender.clone(), spender.clone()), value);
+        // unlock the tokens vested so far
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn claim_vested(origin, token_id: TokenId) -> DispatchResult {
+            let account = ensure_signed(origin)?;
+            ensure!(Self::vesting((token_id, account.clone())).is_some(), "The account has no vesting schedule");
 
-            Self::deposit_event(RawEvent::Approval(sender, spender, value));
+            let locked = Self::vesting_locked(token_id, &account);
+            if locked.is_zero() {
+                <Vesting<T>>::remove((token_id, account.clone()));
+                Self::remove_lock(VESTING_ID, token_id, &account);
+            } else {
+                Self::set_lock(VESTING_ID, token_id, &account, locked);
+            }
+            Self::deposit_event(RawEvent::VestingClaimed(token_id, account, locked));
             Ok(())
         }
 
+        // register a new token and mint its initial supply to the creator
         #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
-        fn transfer_from(origin,
-            from: T::AccountId,
-            to: T::AccountId,
-            token_id: TokenId,
-            #[compact] value: T::Balance
-        ) -> DispatchResult{
-            let sender = ensure_signed(origin)?;
-            let allowance = Self::allowance_of((token_id, from.clone(), sender.clone()));
+        fn create_token(origin, symbol: Vec<u8>, name: Vec<u8>, decimals: u16, #[compact] initial_supply: T::Balance, max_supply: Option<T::Balance>) -> DispatchResult {
+            let creator = ensure_signed(origin)?;
+            ensure!(!name.is_empty(), "The token name is empty");
+            ensure!(name.len() <= MAX_NAME_LENGTH, "The token name is too long");
+            Self::check_symbol_is_free(&symbol)?;
+            ensure!(max_supply.map_or(true, |max| initial_supply <= max), Error::<T>::MaxSupplyExceeded);
 
-            let updated_allowance = allowance.checked_sub(&value).ok_or("Underflow in calculating allowance")?;
+            let deposit = Self::creation_deposit();
+            ensure!(
+                <balances::Module<T> as ReservableCurrency<_>>::can_reserve(&creator, deposit),
+                "Not enough balance for the creation deposit"
+            );
+            let token_id = Self::_create_token(symbol, decimals)?;
+            if let Some(max) = max_supply {
+                <MaxSupply<T>>::insert(token_id, max);
+            }
+            if !initial_supply.is_zero() {
+                Self::_mint(token_id, creator.clone(), initial_supply)?;
+            }
+            <TokenNames>::insert(token_id, name);
+            <TokenCreators<T>>::insert(token_id, (creator.clone(), deposit));
+            <TokenAdmins<T>>::insert(token_id, creator.clone());
+            // checked above, reserved once nothing else can fail
+            <balances::Module<T> as ReservableCurrency<_>>::reserve(&creator, deposit)?;
+            Self::deposit_event(RawEvent::TokenCreated(token_id, creator, initial_supply));
+            Ok(())
+        }
 
+        #[weight = SimpleDispatchInfo::FixedOperational(10_000)]
+        fn set_token_admin(origin, token_id: TokenId, admin: Option<T::AccountId>) -> DispatchResult {
+            ensure_root(origin)?;
+            match &admin {
+                Some(account) => <TokenAdmins<T>>::insert(token_id, account),
+                None => <TokenAdmins<T>>::remove(token_id),
+            }
+            Self::deposit_event(RawEvent::TokenAdminChanged(token_id, admin));
+            Ok(())
+        }
 
-            Self::make_transfer(token_id, from.clone(), to.clone(), value)?;
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn set_minimum_balance(origin, token_id: TokenId, #[compact] minimum: T::Balance) -> DispatchResult {
+            let admin = ensure_signed(origin)?;
+            Self::check_admin(token_id, admin)?;
 
-            <Allowance<T>>::insert((token_id, from, sender), updated_allowance);
+            <MinimumBalance<T>>::insert(token_id, minimum);
+            Self::deposit_event(RawEvent::MinimumBalanceChanged(token_id, minimum));
             Ok(())
         }
 
-    }
-}
-
-impl<T: Trait> Module<T> {
-    pub fn _burn(token_id: TokenId, from: T::AccountId, amount: T::Balance) -> Result<()> {
-        ensure!(
-            Self::total_supply(0) >= amount,
-            "Cannot burn more than total supply"
-        );
+        // None lifts the limit, the admin can change it at any time
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn set_max_holders(origin, token_id: TokenId, max_holders: Option<u32>) -> DispatchResult {
+            let admin = ensure_signed(origin)?;
+            Self::check_admin(token_id, admin)?;
 
-        let free_balance = <Balance<T>>::get((token_id, from.clone()))
-            - <Locked<T>>::get((token_id, from.clone()));
-        ensure!(
-            free_balance > T::Balance::zero(),
-            "Cannot burn with zero balance"
-        );
-        ensure!(free_balance >= amount, "Not enough because of locked funds");
+            match max_holders {
+                Some(max) => <MaxHolders>::insert(token_id, max),
+                None => <MaxHolders>::remove(token_id),
+            }
+            Self::deposit_event(RawEvent::MaxHoldersChanged(token_id, max_holders));
+            Ok(())
+        }
 
-        let next_balance = free_balance
-            .checked_sub(&amount)
-            .ok_or("Underflow subtracting from balance burn")?;
-        let next_total = Self::total_supply(0)
-            .checked_sub(&amount)
-            .ok_or("Underflow subtracting from total supply")?;
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn set_max_holders_exempt(origin, token_id: TokenId, account: T::AccountId, exempt: bool) -> DispatchResult {
+            let admin = ensure_signed(origin)?;
+            Self::check_admin(token_id, admin)?;
 
-        <Balance<T>>::insert((token_id, from.clone()), next_balance);
-        <TotalSupply<T>>::insert(token_id, next_total);
+            if exempt {
+                <MaxHoldersExempt<T>>::insert(token_id, &account, true);
+            } else {
+                <MaxHoldersExempt<T>>::remove(token_id, &account);
+            }
+            Self::deposit_event(RawEvent::MaxHoldersExemptionChanged(token_id, account, exempt));
+            Ok(())
+        }
 
-        Ok(())
-    }
-    pub fn _mint(token_id: TokenId, to: T::AccountId, amount: T::Balance) -> Result<()> {
-        ensure!(!amount.is_zero(), "Amount should be non-zero");
+        // fields left as None are kept, an empty URI removes it, decimals only change how balances are displayed
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn update_metadata(origin, token_id: TokenId, name: Option<Vec<u8>>, decimals: Option<u16>, uri: Option<Vec<u8>>) -> DispatchResult {
+            let admin = ensure_signed(origin)?;
+            Self::check_admin(token_id, admin)?;
+            ensure!(!Self::is_metadata_frozen(token_id), "The token metadata is frozen");
+            if let Some(name) = name.as_ref() {
+                ensure!(!name.is_empty(), "The token name is empty");
+                ensure!(name.len() <= MAX_NAME_LENGTH, "The token name is too long");
+            }
+            ensure!(uri.as_ref().map_or(true, |u| u.len() <= MAX_METADATA_LENGTH), "The metadata is too long");
 
-        let old_balance = <Balance<T>>::get((token_id, to.clone()));
-        let next_balance = old_balance
-            .checked_add(&amount)
-            .ok_or("Overflow adding to balance")?;
-        let next_total = Self::total_supply(0)
-            .checked_add(&amount)
-            .ok_or("Overflow adding to total supply")?;
+            if let Some(name) = name {
+                <TokenNames>::insert(token_id, name);
+            }
+            if let Some(decimals) = decimals {
+                <DisplayDecimals>::insert(token_id, decimals);
+            }
+            match uri {
+                Some(uri) if uri.is_empty() => <TokenMetadata>::remove(token_id),
+                Some(uri) => <TokenMetadata>::insert(token_id, uri),
+                None => (),
+            }
+            Self::deposit_event(RawEvent::MetadataUpdated(
+                token_id,
+                Self::token_name(token_id),
+                Self::display_decimals(token_id),
+                Self::token_metadata(token_id),
+            ));
+            Ok(())
+        }
 
-        <Balance<T>>::insert((token_id, to.clone()), next_balance);
-        <TotalSupply<T>>::insert(token_id, next_total);
+        // makes the metadata immutable, there is no way back
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn freeze_metadata(origin, token_id: TokenId) -> DispatchResult {
+            let admin = ensure_signed(origin)?;
+            Self::check_admin(token_id, admin)?;
+            ensure!(!Self::is_metadata_frozen(token_id), "The token metadata is frozen");
 
-        Ok(())
-    }
+            <MetadataFrozen>::insert(token_id, true);
+            Self::deposit_event(RawEvent::MetadataFrozen(token_id));
+            Ok(())
+        }
 
-    fn make_transfer(
-        token_id: TokenId,
-        from: T::AccountId,
-        to: T::AccountId,
-        amount: T::Balance,
-    ) -> Result<()> {
-        let from_balance = <Balance<T>>::get((token_id, from.clone()));
-        ensure!(from_balance >= amount, "User does not have enough tokens");
-        let free_balance = <Balance<T>>::get((token_id, from.clone()))
-            - <Locked<T>>::get((token_id, from.clone()));
-        ensure!(free_balance >= amount, "Not enough because of locked funds");
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn destroy_token(origin, token_id: TokenId) -> DispatchResult {
+            let admin = ensure_signed(origin)?;
+            Self::check_admin(token_id, admin)?;
+            Self::_destroy_token(token_id)?;
+            Ok(())
+        }
 
-        <Balance<T>>::insert((token_id, from.clone()), from_balance - amount);
-        <Balance<T>>::mutate((token_id, to.clone()), |balance| *balance += amount);
+        // governance path for tokens without an admin, the supply should be burned as well
+        #[weight = SimpleDispatchInfo::FixedOperational(10_000)]
+        fn force_destroy_token(origin, token_id: TokenId) -> DispatchResult {
+            ensure_root(origin)?;
+            Self::_destroy_token(token_id)?;
+            Ok(())
+        }
 
-        Self::deposit_event(RawEvent::Transfer(from, to, amount));
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn transfer_ownership(origin, token_id: TokenId, new_admin: T::AccountId) -> DispatchResult {
+            let admin = ensure_signed(origin)?;
+            Self::check_admin(token_id, admin)?;
 
-        Ok(())
-    }
-    pub fn lock(token_id: TokenId, account: T::AccountId, amount: T::Balance) -> Result<()> {
-        //TODO: substract this amount from the main balance?
-        //              Balance: 1000, Locked: 0
-        // lock(400) => Balance: 1000, Locked: 400 or
-        // lock(400) => Balance: 600, Locked: 400
-        <Locked<T>>::insert((token_id, account.clone()), amount);
+            <TokenAdmins<T>>::insert(token_id, &new_admin);
+            Self::deposit_event(RawEvent::TokenAdminChanged(token_id, Some(new_admin)));
+            Ok(())
+        }
 
-        Ok(())
-    }
-    pub fn unlock(token_id: TokenId, account: &T::AccountId, amount: T::Balance) -> Result<()> {
-        //TODO: add this amount to the main balance?
-        //                Balance: 1000, Locked: 400
-        // unlock(400) => Balance: 1000, Locked: 0 or
-        // unlock(400) => Balance: 1400, Locked: 0
-        let balance = <Locked<T>>::get((token_id, account.clone()));
-        let new_balance = balance
-            .checked_sub(&amount)
-            .expect("Underflow while unlocking. Check if user has enough locked funds.");
-        let zero = T::Balance::zero();
+        // the token can be managed only by the root afterwards
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn renounce_ownership(origin, token_id: TokenId) -> DispatchResult {
+            let admin = ensure_signed(origin)?;
+            Self::check_admin(token_id, admin)?;
 
-        match new_balance {
-            b if b == zero => <Locked<T>>::remove((token_id, account.clone())),
-            _ => <Locked<T>>::insert((token_id, account.clone()), new_balance),
+            <TokenAdmins<T>>::remove(token_id);
+            Self::deposit_event(RawEvent::TokenAdminChanged(token_id, None));
+            Ok(())
         }
-        Ok(())
-    }
-    // Token management
-    // Add new or do nothing
-    pub fn check_token_exist(token: &Vec<u8>) -> Result<()> {
-        if !<TokenIds>::contains_key(token.clone()) {
-            Self::validate_name(token)
-        } else {
+
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn grant_role(origin, token_id: TokenId, role: TokenRole, account: T::AccountId) -> DispatchResult {
+            let admin = ensure_signed(origin)?;
+            Self::check_admin(token_id, admin)?;
+
+            Self::_grant_role(token_id, role, &account);
+            Self::deposit_event(RawEvent::RoleGranted(token_id, role, account));
             Ok(())
         }
-    }
 
-    fn validate_name(name: &[u8]) -> Result<()> {
-        if name.len() > 10 {
-            return Err("The token symbol is too long");
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn revoke_role(origin, token_id: TokenId, role: TokenRole, account: T::AccountId) -> DispatchResult {
+            let admin = ensure_signed(origin)?;
+            Self::check_admin(token_id, admin)?;
+
+            Self::_revoke_role(token_id, role, &account);
+            Self::deposit_event(RawEvent::RoleRevoked(token_id, role, account));
+            Ok(())
         }
-        if name.len() < 3 {
-            return Err("The token symbol is too short");
+
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn freeze_account(origin, token_id: TokenId, account: T::AccountId) -> DispatchResult {
+            let admin = ensure_signed(origin)?;
+            Self::check_admin(token_id, admin)?;
+
+            <FrozenAccounts<T>>::insert(token_id, &account, true);
+            Self::deposit_event(RawEvent::AccountFrozen(token_id, account));
+            Ok(())
         }
 
-        Ok(())
-    }
-}
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn thaw_account(origin, token_id: TokenId, account: T::AccountId) -> DispatchResult {
+            let admin = ensure_signed(origin)?;
+            Self::check_admin(token_id, admin)?;
 
-/// tests for this module
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use frame_support::{
-        assert_noop, assert_ok, impl_outer_origin, parameter_types, traits::Get, weights::Weight,
-    };
-    use sp_core::H256;
-    use sp_runtime::{
-        testing::Header,
-        traits::{BlakeTwo256, IdentityLookup},
-        Perbill,
-    };
-    use std::cell::RefCell;
+            <FrozenAccounts<T>>::remove(token_id, &account);
+            Self::deposit_event(RawEvent::AccountThawed(token_id, account));
+            Ok(())
+        }
 
-    pub type Balance = u128;
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn set_transfer_fee(origin, token_id: TokenId, fee: Option<(TransferFee<T::Balance>, T::AccountId)>) -> DispatchResult {
+            let admin = ensure_signed(origin)?;
+            Self::check_admin(token_id, admin)?;
 
-    thread_local! {
-        static EXISTENTIAL_DEPOSIT: RefCell<u128> = RefCell::new(500);
-    }
+            match &fee {
+                Some(fee) => <TransferFees<T>>::insert(token_id, fee),
+                None => <TransferFees<T>>::remove(token_id),
+            }
+            Self::deposit_event(RawEvent::TransferFeeChanged(token_id, fee));
+            Ok(())
+        }
 
-    impl_outer_origin! {
-        pub enum Origin for Test {}
-    }
-    pub struct ExistentialDeposit;
-    impl Get<u128> for ExistentialDeposit {
-        fn get() -> u128 {
-            EXISTENTIAL_DEPOSIT.with(|v| *v.borrow())
+        // freeze the balances of the token at the current block, e.g. for token-weighted voting
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn create_snapshot(origin, token_id: TokenId) -> DispatchResult {
+            let admin = ensure_signed(origin)?;
+            Self::check_admin(token_id, admin)?;
+            Self::_create_snapshot(token_id)?;
+            Ok(())
         }
-    }
-    impl Get<u64> for ExistentialDeposit {
-        fn get() -> u64 {
-            EXISTENTIAL_DEPOSIT.with(|v| *v.borrow() as u64)
+
+        // share the amount of the source token between the holders of the token, pro-rata to their balances
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn distribute(origin, token_id: TokenId, source_token_id: TokenId, #[compact] total_amount: T::Balance) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            ensure!(!total_amount.is_zero(), "Distribution amount should be non-zero");
+            ensure!(source_token_id != token_id, "The token can not be distributed to its own holders");
+            Self::check_token_exists(token_id)?;
+            // the distributor does not take a share of its own distribution
+            let own = Self::balance_of((token_id, sender.clone()));
+            let supply = Self::total_supply(token_id).saturating_sub(own);
+            ensure!(!supply.is_zero(), "The token has no holders");
+            let id = Self::distribution_count();
+            let next = id.checked_add(1).ok_or("Overflow adding new distribution")?;
+
+            Self::make_transfer(source_token_id, sender.clone(), Self::distribution_account(), total_amount)?;
+            let snapshot = Self::_create_snapshot(token_id)?;
+            if !own.is_zero() {
+                <DistributionClaims<T>>::insert(id, &sender, true);
+            }
+            <Distributions<T>>::insert(id, Distribution {
+                token: token_id,
+                snapshot,
+                source_token: source_token_id,
+                amount: total_amount,
+                supply,
+            });
+            <DistributionCount>::put(next);
+            Self::deposit_event(RawEvent::DistributionCreated(id, token_id, source_token_id, total_amount, snapshot));
+            Ok(())
         }
-    }
 
-    // For testing the module, we construct most of a mock runtime. This means
-    // first constructing a configuration type (`Test`) which `impl`s each of the
-    // configuration traits of modules we want to use.
-    #[derive(Clone, Eq, PartialEq)]
-    pub struct Test;
-    parameter_types! {
-        pub const BlockHashCount: u64 = 250;
-        pub const MaximumBlockWeight: Weight = 1024;
-        pub const MaximumBlockLength: u32 = 2 * 1024;
-        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
-    }
-    impl system::Trait for Test {
-        type Origin = Origin;
-        type Call = ();
-        type Index = u64;
-        type BlockNumber = u64;
-        type Hash = H256;
-        type Hashing = BlakeTwo256;
-        type AccountId = u64;
-        type Lookup = IdentityLookup<Self::AccountId>;
-        type Header = Header;
-        type Event = ();
-        type BlockHashCount = BlockHashCount;
-        type MaximumBlockWeight = MaximumBlockWeight;
-        type MaximumBlockLength = MaximumBlockLength;
-        type AvailableBlockRatio = AvailableBlockRatio;
-        type Version = ();
-        type ModuleToIndex = ();
-        type AccountData = balances::AccountData<u128>;
-        type OnNewAccount = ();
-        type OnKilledAccount = ();
-    }
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn claim_distribution(origin, distribution_id: DistributionId) -> DispatchResult {
+            let holder = ensure_signed(origin)?;
+            let distribution = Self::distribution(distribution_id).ok_or("The distribution does not exist")?;
+            ensure!(
+                !Self::distribution_claimed(distribution_id, &holder),
+                "The distribution is already claimed"
+            );
+            let balance = Self::balance_at(distribution.token, &holder, distribution.snapshot)?;
+            let share = multiply_by_rational(
+                distribution.amount.saturated_into(),
+                balance.saturated_into(),
+                distribution.supply.saturated_into(),
+            )?;
+            let share: T::Balance = share.saturated_into();
+            ensure!(!share.is_zero(), "Nothing to claim");
 
-    impl balances::Trait for Test {
-        type Balance = Balance;
-        type DustRemoval = ();
-        type Event = ();
-        type ExistentialDeposit = ExistentialDeposit;
-        type AccountStore = system::Module<Test>;
-    }
+            Self::make_transfer(distribution.source_token, Self::distribution_account(), holder.clone(), share)?;
+            <DistributionClaims<T>>::insert(distribution_id, &holder, true);
+            Self::deposit_event(RawEvent::DistributionClaimed(distribution_id, holder, share));
+            Ok(())
+        }
 
-    parameter_types! {
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn set_soulbound(origin, token_id: TokenId, soulbound: bool) -> DispatchResult {
+            let admin = ensure_signed(origin)?;
+            Self::check_admin(token_id, admin)?;
+
+            <Soulbound>::insert(token_id, soulbound);
+            Self::deposit_event(RawEvent::SoulboundChanged(token_id, soulbound));
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn set_whitelist_only(origin, token_id: TokenId, enabled: bool) -> DispatchResult {
+            let admin = ensure_signed(origin)?;
+            Self::check_admin(token_id, admin)?;
+
+            <WhitelistOnly>::insert(token_id, enabled);
+            Self::deposit_event(RawEvent::WhitelistOnlyChanged(token_id, enabled));
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn add_to_whitelist(origin, token_id: TokenId, account: T::AccountId) -> DispatchResult {
+            let admin = ensure_signed(origin)?;
+            Self::check_admin(token_id, admin)?;
+
+            <Whitelist<T>>::insert(token_id, &account, true);
+            Self::deposit_event(RawEvent::Whitelisted(token_id, account));
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn remove_from_whitelist(origin, token_id: TokenId, account: T::AccountId) -> DispatchResult {
+            let admin = ensure_signed(origin)?;
+            Self::check_admin(token_id, admin)?;
+
+            <Whitelist<T>>::remove(token_id, &account);
+            Self::deposit_event(RawEvent::RemovedFromWhitelist(token_id, account));
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn pause_token(origin, token_id: TokenId) -> DispatchResult {
+            let admin = ensure_signed(origin)?;
+            Self::check_admin(token_id, admin)?;
+
+            <PausedTokens>::insert(token_id, true);
+            Self::deposit_event(RawEvent::TokenPaused(token_id));
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn unpause_token(origin, token_id: TokenId) -> DispatchResult {
+            let admin = ensure_signed(origin)?;
+            Self::check_admin(token_id, admin)?;
+
+            <PausedTokens>::remove(token_id);
+            Self::deposit_event(RawEvent::TokenUnpaused(token_id));
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedOperational(10_000)]
+        fn set_wrapped_native(origin, token_id: TokenId) -> DispatchResult {
+            ensure_root(origin)?;
+            Self::check_token_exists(token_id)?;
+            // only wrap mints the token, it is backed by the locked native currency
+            ensure!(
+                Self::token_admin(token_id).is_none() && Self::role_holders((token_id, TokenRole::Minter)) == 0,
+                "The wrapped token has an admin or minters"
+            );
+            <WrappedNative>::put(token_id);
+            Self::deposit_event(RawEvent::WrappedNativeSet(token_id));
+            Ok(())
+        }
+
+        // lock the native currency and mint the same amount of the wrapped token
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn wrap(origin, #[compact] amount: T::Balance) -> DispatchResult {
+            let account = ensure_signed(origin)?;
+            let token_id = Self::wrapped_native().ok_or("The wrapped native token is not set")?;
+            ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+            // the mint can't fail after the native currency is taken
+            Self::check_max_supply(token_id, amount)?;
+            Self::check_minimum_balance(token_id, Self::balance_of((token_id, account.clone())).saturating_add(amount))?;
+            Self::check_max_holders(token_id, Self::new_holders(token_id, &[&account]))?;
+
+            <balances::Module<T> as Currency<_>>::transfer(
+                &account,
+                &Self::wrap_account(),
+                amount,
+                ExistenceRequirement::KeepAlive,
+            )?;
+            Self::_mint(token_id, account.clone(), amount)?;
+            Self::deposit_event(RawEvent::Wrapped(account, amount));
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn unwrap(origin, #[compact] amount: T::Balance) -> DispatchResult {
+            let account = ensure_signed(origin)?;
+            let token_id = Self::wrapped_native().ok_or("The wrapped native token is not set")?;
+            ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+
+            // burn first, a failed payout only has to give the tokens back
+            Self::_burn(token_id, account.clone(), amount)?;
+            if let Err(e) = <balances::Module<T> as Currency<_>>::transfer(
+                &Self::wrap_account(),
+                &account,
+                amount,
+                ExistenceRequirement::AllowDeath,
+            ) {
+                Self::_mint(token_id, account.clone(), amount)?;
+                return Err(e);
+            }
+            Self::deposit_event(RawEvent::Unwrapped(account, amount));
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedOperational(10_000)]
+        fn set_creation_deposit(origin, #[compact] deposit: T::Balance) -> DispatchResult {
+            ensure_root(origin)?;
+            <CreationDeposit<T>>::put(deposit);
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn transfer(origin,
+            to: <T::Lookup as StaticLookup>::Source,
+            token_id: TokenId,
+            #[compact] amount: T::Balance
+        ) -> DispatchResult{
+            let sender = ensure_signed(origin)?;
+            let to = T::Lookup::lookup(to)?;
+            ensure!(!amount.is_zero(), "Transfer Amount should be non-zero");
+
+            Self::make_charged_transfer(token_id, sender, to, amount)?;
+            Ok(())
+        }
+
+        // fails instead of reaping the token account of the sender
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn transfer_keep_alive(origin,
+            to: <T::Lookup as StaticLookup>::Source,
+            token_id: TokenId,
+            #[compact] amount: T::Balance
+        ) -> DispatchResult{
+            let sender = ensure_signed(origin)?;
+            let to = T::Lookup::lookup(to)?;
+            ensure!(!amount.is_zero(), "Transfer Amount should be non-zero");
+            ensure!(Self::balance_of((token_id, sender.clone())) > amount, "The transfer would reap the sender account");
+
+            Self::make_charged_transfer(token_id, sender, to, amount)?;
+            Ok(())
+        }
+
+        // sends the whole usable balance, or leaves the token minimum with keep_alive
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn transfer_all(origin, token_id: TokenId, to: <T::Lookup as StaticLookup>::Source, keep_alive: bool) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            let to = T::Lookup::lookup(to)?;
+            let balance = Self::balance_of((token_id, sender.clone()));
+            let mut amount = Self::usable_balance(token_id, &sender);
+            if keep_alive {
+                let keep = Self::minimum_balance(token_id).max(One::one());
+                amount = amount.min(balance.saturating_sub(keep));
+            }
+            ensure!(!amount.is_zero(), "Nothing to transfer");
+
+            Self::make_charged_transfer(token_id, sender, to, amount)?;
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn transfer_with_memo(origin, token_id: TokenId, to: T::AccountId, #[compact] amount: T::Balance, memo: Vec<u8>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            ensure!(!amount.is_zero(), "Transfer Amount should be non-zero");
+            ensure!(memo.len() <= MAX_MEMO_LENGTH, Error::<T>::MemoTooLong);
+
+            Self::make_charged_transfer(token_id, sender.clone(), to.clone(), amount)?;
+            Self::deposit_event(RawEvent::TransferWithMemo(token_id, sender, to, amount, memo));
+            Ok(())
+        }
+
+        // all transfers are checked before the first one is made, so the batch is all-or-nothing
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn transfer_batch(origin, token_id: TokenId, transfers: Vec<(T::AccountId, T::Balance)>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            ensure!(!transfers.is_empty(), "The batch is empty");
+            ensure!(transfers.len() <= MAX_BATCH_SIZE, "The batch is too large");
+
+            let mut total = T::Balance::zero();
+            for (to, amount) in transfers.iter() {
+                ensure!(!amount.is_zero(), "Transfer Amount should be non-zero");
+                Self::check_transfer_allowed(token_id, &sender, to)?;
+                Self::fee_of(token_id, *amount)?;
+                total = total.checked_add(amount).ok_or("Overflow adding batch amounts")?;
+            }
+            let mut recipients: Vec<&T::AccountId> = transfers.iter().map(|(to, _)| to).collect();
+            let fee_beneficiary = Self::transfer_fee(token_id).map(|(_, beneficiary)| beneficiary);
+            if let Some(beneficiary) = fee_beneficiary.as_ref() {
+                Self::check_transfer_allowed(token_id, &sender, beneficiary)?;
+                recipients.push(beneficiary);
+            }
+            Self::check_max_holders(token_id, Self::new_holders(token_id, &recipients))?;
+            ensure!(Self::balance_of((token_id, sender.clone())) >= total, Error::<T>::InsufficientBalance);
+            ensure!(Self::usable_balance(token_id, &sender) >= total, Error::<T>::LockedFunds);
+            // each transfer is checked against the balances the earlier ones leave
+            let mut balances = Vec::new();
+            for (to, amount) in transfers.iter() {
+                let fee = Self::fee_of(token_id, *amount)?;
+                Self::check_transfer_step(token_id, &mut balances, &sender, to, *amount - fee)?;
+                if let Some(beneficiary) = fee_beneficiary.as_ref().filter(|_| !fee.is_zero()) {
+                    Self::check_transfer_step(token_id, &mut balances, &sender, beneficiary, fee)?;
+                }
+            }
+
+            for (to, amount) in transfers {
+                Self::make_charged_transfer(token_id, sender.clone(), to, amount)?;
+            }
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn approve(origin,
+            spender: <T::Lookup as StaticLookup>::Source,
+            token_id: TokenId,
+            #[compact] value: T::Balance
+        ) -> DispatchResult{
+            let sender = ensure_signed(origin)?;
+            let spender = T::Lookup::lookup(spender)?;
+
+            Self::track_account(token_id, &sender);
+            <Allowances<T>>::insert((token_id, sender.clone()), spender.clone(), value);
+
+            Self::deposit_event(RawEvent::Approval(token_id, sender, spender, value));
+            Ok(())
+        }
+
+        // approves the operator for all tokens of the sender, false revokes it
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn set_operator(origin, operator: <T::Lookup as StaticLookup>::Source, approved: bool) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            let operator = T::Lookup::lookup(operator)?;
+            ensure!(sender != operator, "The owner can't be its own operator");
+
+            if approved {
+                <Operators<T>>::insert(&sender, &operator, true);
+            } else {
+                <Operators<T>>::remove(&sender, &operator);
+            }
+            Self::deposit_event(RawEvent::OperatorSet(sender, operator, approved));
+            Ok(())
+        }
+
+        // approval signed by the owner off-chain and submitted by anyone, e.g. a relayer paying the fee
+        // the owner signs the encoded ("permit", genesis_hash, owner, spender, token_id, amount, deadline, nonce)
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn permit(origin,
+            owner: T::AccountId,
+            spender: T::AccountId,
+            token_id: TokenId,
+            #[compact] amount: T::Balance,
+            deadline: T::BlockNumber,
+            signature: T::Signature
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            ensure!(<system::Module<T>>::block_number() <= deadline, "The permit has expired");
+            let nonce = Self::permit_nonce(&owner);
+            let payload = (b"permit", Self::genesis_hash(), &owner, &spender, token_id, amount, deadline, nonce).encode();
+            ensure!(signature.verify(&payload[..], &owner), "Invalid permit signature");
+            let next = nonce.checked_add(1).ok_or("Overflow incrementing permit nonce")?;
+
+            <PermitNonces<T>>::insert(&owner, next);
+            Self::track_account(token_id, &owner);
+            <Allowances<T>>::insert((token_id, owner.clone()), spender.clone(), amount);
+            Self::deposit_event(RawEvent::Approval(token_id, owner, spender, amount));
+            Ok(())
+        }
+
+        // change the allowance relative to its current value, not racing with transfer_from
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn increase_allowance(origin,
+            spender: <T::Lookup as StaticLookup>::Source,
+            token_id: TokenId,
+            #[compact] value: T::Balance
+        ) -> DispatchResult{
+            let sender = ensure_signed(origin)?;
+            let spender = T::Lookup::lookup(spender)?;
+            let allowance = Self::allowance_of((token_id, sender.clone()), spender.clone())
+                .checked_add(&value)
+                .ok_or("Overflow increasing allowance")?;
+
+            Self::track_account(token_id, &sender);
+            <Allowances<T>>::insert((token_id, sender.clone()), spender.clone(), allowance);
+            Self::deposit_event(RawEvent::Approval(token_id, sender, spender, allowance));
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn decrease_allowance(origin,
+            spender: <T::Lookup as StaticLookup>::Source,
+            token_id: TokenId,
+            #[compact] value: T::Balance
+        ) -> DispatchResult{
+            let sender = ensure_signed(origin)?;
+            let spender = T::Lookup::lookup(spender)?;
+            let allowance = Self::allowance_of((token_id, sender.clone()), spender.clone())
+                .checked_sub(&value)
+                .ok_or("Allowance is below the decreased amount")?;
+
+            Self::track_account(token_id, &sender);
+            <Allowances<T>>::insert((token_id, sender.clone()), spender.clone(), allowance);
+            Self::deposit_event(RawEvent::Approval(token_id, sender, spender, allowance));
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+        fn transfer_from(origin,
+            from: T::AccountId,
+            to: T::AccountId,
+            token_id: TokenId,
+            #[compact] value: T::Balance
+        ) -> DispatchResult{
+            let sender = ensure_signed(origin)?;
+            ensure!(!value.is_zero(), "Transfer Amount should be non-zero");
+
+            // operators don't spend the allowance
+            if Self::is_operator(&from, &sender) {
+                Self::make_charged_transfer(token_id, from, to, value)?;
+                return Ok(());
+            }
+            let allowance = Self::allowance_of((token_id, from.clone()), sender.clone());
+
+            let updated_allowance = allowance.checked_sub(&value).ok_or("Underflow in calculating allowance")?;
+
+            Self::make_charged_transfer(token_id, from.clone(), to, value)?;
+
+            Self::track_account(token_id, &from);
+            <Allowances<T>>::insert((token_id, from.clone()), sender.clone(), updated_allowance);
+            Self::deposit_event(RawEvent::Approval(token_id, from, sender, updated_allowance));
+            Ok(())
+        }
+
+    }
+}
+
+impl<T: Trait> Module<T> {
+    /// mint on behalf of an account or a module holding the minter role
+    pub fn mint_as(minter: &T::AccountId, token_id: TokenId, to: T::AccountId, amount: T::Balance) -> TokenResult<(), T> {
+        ensure!(Self::has_role(token_id, (TokenRole::Minter, minter)), Error::<T>::NotMinter);
+        Self::_mint(token_id, to, amount)
+    }
+    /// burn on behalf of an account or a module holding the burner role, the reason and memo go to the Burn event
+    pub fn burn_as(
+        burner: &T::AccountId,
+        token_id: TokenId,
+        from: T::AccountId,
+        amount: T::Balance,
+        reason: Option<BurnReason>,
+        memo: Option<Vec<u8>>,
+    ) -> TokenResult<(), T> {
+        ensure!(Self::has_role(token_id, (TokenRole::Burner, burner)), Error::<T>::NotBurner);
+        ensure!(memo.as_ref().map_or(true, |m| m.len() <= MAX_MEMO_LENGTH), Error::<T>::MemoTooLong);
+        Self::_burn(token_id, from.clone(), amount)?;
+        Self::deposit_event(RawEvent::Burn(token_id, from, amount, reason, memo));
+        Ok(())
+    }
+    fn check_admin(token_id: TokenId, account: T::AccountId) -> Result<()> {
+        ensure!(Self::token_admin(token_id) == Some(account), "Only the token admin can manage the token");
+        Ok(())
+    }
+    /// signed messages are bound to the chain by its genesis hash
+    fn genesis_hash() -> T::Hash {
+        <system::Module<T>>::block_hash(T::BlockNumber::zero())
+    }
+    pub fn _grant_role(token_id: TokenId, role: TokenRole, account: &T::AccountId) {
+        if !Self::has_role(token_id, (role, account.clone())) {
+            <TokenRoles<T>>::insert(token_id, (role, account), true);
+            <RoleHolders>::mutate((token_id, role), |count| *count = count.saturating_add(1));
+        }
+    }
+    pub fn _revoke_role(token_id: TokenId, role: TokenRole, account: &T::AccountId) {
+        if Self::has_role(token_id, (role, account.clone())) {
+            <TokenRoles<T>>::remove(token_id, (role, account));
+            <RoleHolders>::mutate((token_id, role), |count| *count = count.saturating_sub(1));
+        }
+    }
+    pub fn _burn(token_id: TokenId, from: T::AccountId, amount: T::Balance) -> TokenResult<(), T> {
+        ensure!(
+            <Balance<T>>::get((token_id, from.clone())) >= amount,
+            Error::<T>::InsufficientBalance
+        );
+        // the balance covers the amount, only the locks can make it short
+        ensure!(Self::usable_balance(token_id, &from) >= amount, Error::<T>::LockedFunds);
+        ensure!(
+            Self::total_supply(token_id) >= amount,
+            Error::<T>::Underflow
+        );
+
+        let next_balance = <Balance<T>>::get((token_id, from.clone()))
+            .checked_sub(&amount)
+            .ok_or(Error::<T>::Underflow)?;
+        Self::check_minimum_balance(token_id, next_balance)?;
+        let next_total = Self::total_supply(token_id)
+            .checked_sub(&amount)
+            .ok_or(Error::<T>::Underflow)?;
+
+        Self::snapshot_supply(token_id);
+        Self::set_balance(token_id, &from, next_balance);
+        <TotalSupply<T>>::insert(token_id, next_total);
+        T::OnTransfer::on_burn(token_id, &from, amount);
+
+        Ok(())
+    }
+    pub fn _mint(token_id: TokenId, to: T::AccountId, amount: T::Balance) -> TokenResult<(), T> {
+        ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+        Self::check_token_exists(token_id)?;
+
+        let old_balance = <Balance<T>>::get((token_id, to.clone()));
+        let next_balance = old_balance
+            .checked_add(&amount)
+            .ok_or(Error::<T>::Overflow)?;
+        Self::check_minimum_balance(token_id, next_balance)?;
+        Self::check_max_holders(token_id, Self::new_holders(token_id, &[&to]))?;
+        let next_total = Self::check_max_supply(token_id, amount)?;
+
+        Self::snapshot_supply(token_id);
+        Self::set_balance(token_id, &to, next_balance);
+        <TotalSupply<T>>::insert(token_id, next_total);
+        T::OnTransfer::on_mint(token_id, &to, amount);
+
+        Ok(())
+    }
+
+    /// fee of the transfer, taken out of the transferred amount
+    pub fn fee_of(token_id: TokenId, amount: T::Balance) -> TokenResult<T::Balance, T> {
+        let fee = match Self::transfer_fee(token_id) {
+            Some((TransferFee::Flat(fee), _)) => fee,
+            Some((TransferFee::Proportional(share), _)) => share * amount,
+            None => T::Balance::zero(),
+        };
+        ensure!(fee < amount, Error::<T>::FeeNotCovered);
+        Ok(fee)
+    }
+    /// transfer made by the holder, the recipient gets the amount net of the token fee
+    pub fn make_charged_transfer(
+        token_id: TokenId,
+        from: T::AccountId,
+        to: T::AccountId,
+        amount: T::Balance,
+    ) -> TokenResult<(), T> {
+        let fee = Self::fee_of(token_id, amount)?;
+        let beneficiary = match Self::transfer_fee(token_id) {
+            Some((_, beneficiary)) if !fee.is_zero() => beneficiary,
+            _ => return Self::make_transfer(token_id, from, to, amount),
+        };
+        // both transfers are checked before the first one is made
+        Self::check_transfer_allowed(token_id, &from, &beneficiary)?;
+        ensure!(Self::usable_balance(token_id, &from) >= amount, Error::<T>::LockedFunds);
+        Self::check_max_holders(token_id, Self::new_holders(token_id, &[&to, &beneficiary]))?;
+
+        let net = amount - fee;
+        let mut balances = Vec::new();
+        Self::check_transfer_step(token_id, &mut balances, &from, &to, net)?;
+        Self::check_transfer_step(token_id, &mut balances, &from, &beneficiary, fee)?;
+
+        Self::make_transfer(token_id, from.clone(), to.clone(), net)?;
+        Self::make_transfer(token_id, from.clone(), beneficiary, fee)?;
+        Self::deposit_event(RawEvent::TransferFeeCharged(token_id, from, to, net, fee));
+        Ok(())
+    }
+    /// checks a transfer against the balances left by the earlier steps of the same call, without writing them
+    fn check_transfer_step(
+        token_id: TokenId,
+        balances: &mut Vec<(T::AccountId, T::Balance)>,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        amount: T::Balance,
+    ) -> TokenResult<(), T> {
+        if from == to {
+            return Ok(());
+        }
+        let current = |balances: &[(T::AccountId, T::Balance)], account: &T::AccountId| {
+            balances
+                .iter()
+                .find(|(a, _)| a == account)
+                .map(|(_, balance)| *balance)
+                .unwrap_or_else(|| Self::balance_of((token_id, account.clone())))
+        };
+        let next_from = current(balances, from)
+            .checked_sub(&amount)
+            .ok_or(Error::<T>::InsufficientBalance)?;
+        let next_to = current(balances, to)
+            .checked_add(&amount)
+            .ok_or(Error::<T>::Overflow)?;
+        Self::check_minimum_balance(token_id, next_from)?;
+        Self::check_minimum_balance(token_id, next_to)?;
+
+        balances.retain(|(a, _)| a != from && a != to);
+        balances.push((from.clone(), next_from));
+        balances.push((to.clone(), next_to));
+        Ok(())
+    }
+    /// total supply after minting the amount, if it fits under the max supply
+    pub fn check_max_supply(token_id: TokenId, amount: T::Balance) -> TokenResult<T::Balance, T> {
+        let next_total = Self::total_supply(token_id)
+            .checked_add(&amount)
+            .ok_or(Error::<T>::Overflow)?;
+        ensure!(
+            Self::max_supply(token_id).map_or(true, |max| next_total <= max),
+            Error::<T>::MaxSupplyExceeded
+        );
+        Ok(next_total)
+    }
+
+    /// balance should be either empty, so its entry is removed, or not below the token minimum
+    pub fn check_minimum_balance(token_id: TokenId, balance: T::Balance) -> TokenResult<(), T> {
+        ensure!(
+            balance.is_zero() || balance >= Self::minimum_balance(token_id),
+            Error::<T>::BelowMinimumBalance
+        );
+        Ok(())
+    }
+    /// distinct accounts without a balance, which would become holders of the token limited by the max holders
+    pub fn new_holders(token_id: TokenId, accounts: &[&T::AccountId]) -> u32 {
+        let mut new: Vec<&T::AccountId> = Vec::new();
+        for &account in accounts {
+            if Self::balance_of((token_id, account.clone())).is_zero()
+                && !Self::is_max_holders_exempt(token_id, account)
+                && !new.contains(&account)
+            {
+                new.push(account);
+            }
+        }
+        new.len() as u32
+    }
+    pub fn check_max_holders(token_id: TokenId, new_holders: u32) -> TokenResult<(), T> {
+        if new_holders > 0 {
+            if let Some(max) = Self::max_holders(token_id) {
+                ensure!(Self::holder_count(token_id).saturating_add(new_holders) <= max, Error::<T>::MaxHoldersExceeded);
+            }
+        }
+        Ok(())
+    }
+    /// checks of the token and the accounts, not of the balances
+    pub fn check_transfer_allowed(token_id: TokenId, from: &T::AccountId, to: &T::AccountId) -> TokenResult<(), T> {
+        ensure!(!Self::is_paused(token_id), Error::<T>::TokenPaused);
+        ensure!(!Self::is_soulbound(token_id), Error::<T>::NotTransferable);
+        ensure!(!Self::is_frozen(token_id, from), Error::<T>::SenderFrozen);
+        ensure!(!Self::is_frozen(token_id, to), Error::<T>::RecipientFrozen);
+        if Self::whitelist_only(token_id) {
+            ensure!(Self::is_whitelisted(token_id, from), Error::<T>::SenderNotWhitelisted);
+            ensure!(Self::is_whitelisted(token_id, to), Error::<T>::RecipientNotWhitelisted);
+        }
+        Ok(())
+    }
+    pub fn make_transfer(
+        token_id: TokenId,
+        from: T::AccountId,
+        to: T::AccountId,
+        amount: T::Balance,
+    ) -> TokenResult<(), T> {
+        Self::check_transfer_allowed(token_id, &from, &to)?;
+        let next_from = Self::balance_of((token_id, from.clone()))
+            .checked_sub(&amount)
+            .ok_or(Error::<T>::InsufficientBalance)?;
+        ensure!(Self::usable_balance(token_id, &from) >= amount, Error::<T>::LockedFunds);
+
+        if from != to {
+            let next_to = Self::balance_of((token_id, to.clone()))
+                .checked_add(&amount)
+                .ok_or(Error::<T>::Overflow)?;
+            Self::check_minimum_balance(token_id, next_from)?;
+            Self::check_minimum_balance(token_id, next_to)?;
+            // the sender leaving makes room for the recipient
+            if !next_from.is_zero() {
+                Self::check_max_holders(token_id, Self::new_holders(token_id, &[&to]))?;
+            }
+            Self::set_balance(token_id, &from, next_from);
+            Self::set_balance(token_id, &to, next_to);
+        }
+        T::OnTransfer::on_transfer(token_id, &from, &to, amount);
+
+        Self::deposit_event(RawEvent::Transfer(from, to, amount));
+
+        Ok(())
+    }
+    pub fn lock(token_id: TokenId, account: T::AccountId, amount: T::Balance) -> TokenResult<(), T> {
+        //TODO: substract this amount from the main balance?
+        //              Balance: 1000, Locked: 0
+        // lock(400) => Balance: 1000, Locked: 400 or
+        // lock(400) => Balance: 600, Locked: 400
+        let locked = <Locked<T>>::get((token_id, account.clone()))
+            .checked_add(&amount)
+            .ok_or(Error::<T>::Overflow)?;
+        ensure!(
+            <Balance<T>>::get((token_id, account.clone())) >= locked,
+            Error::<T>::InsufficientBalance
+        );
+        // vesting and other named locks can't back the lock as well
+        ensure!(Self::usable_balance(token_id, &account) >= amount, Error::<T>::LockedFunds);
+        Self::track_account(token_id, &account);
+        <Locked<T>>::insert((token_id, account.clone()), locked);
+
+        Ok(())
+    }
+    pub fn unlock(token_id: TokenId, account: &T::AccountId, amount: T::Balance) -> TokenResult<(), T> {
+        //TODO: add this amount to the main balance?
+        //                Balance: 1000, Locked: 400
+        // unlock(400) => Balance: 1000, Locked: 0 or
+        // unlock(400) => Balance: 1400, Locked: 0
+        let balance = <Locked<T>>::get((token_id, account.clone()));
+        let new_balance = balance
+            .checked_sub(&amount)
+            .ok_or(Error::<T>::InsufficientLocked)?;
+        let zero = T::Balance::zero();
+
+        match new_balance {
+            b if b == zero => <Locked<T>>::remove((token_id, account.clone())),
+            _ => <Locked<T>>::insert((token_id, account.clone()), new_balance),
+        }
+        Ok(())
+    }
+    pub fn _mint_vested(
+        token_id: TokenId,
+        to: T::AccountId,
+        schedule: VestingSchedule<T::Balance, T::BlockNumber>,
+    ) -> Result<()> {
+        Self::check_vesting(token_id, &to, &schedule)?;
+        Self::_mint(token_id, to.clone(), schedule.locked)?;
+        Self::add_vesting(token_id, &to, schedule)
+    }
+    /// lock tokens the account already has under the schedule
+    pub fn add_vesting(
+        token_id: TokenId,
+        account: &T::AccountId,
+        schedule: VestingSchedule<T::Balance, T::BlockNumber>,
+    ) -> Result<()> {
+        Self::check_vesting(token_id, account, &schedule)?;
+        <Vesting<T>>::insert((token_id, account.clone()), schedule);
+        Self::set_lock(VESTING_ID, token_id, account, schedule.locked);
+        Self::deposit_event(RawEvent::VestingCreated(token_id, account.clone(), schedule.locked));
+        Ok(())
+    }
+    pub fn check_vesting(
+        token_id: TokenId,
+        account: &T::AccountId,
+        schedule: &VestingSchedule<T::Balance, T::BlockNumber>,
+    ) -> Result<()> {
+        ensure!(!schedule.per_block.is_zero(), "Vesting should unlock a non-zero amount per block");
+        ensure!(
+            Self::vesting((token_id, account.clone())).is_none(),
+            "The account already has a vesting schedule"
+        );
+        Ok(())
+    }
+    /// amount of the schedule which is still locked at the current block
+    pub fn vesting_locked(token_id: TokenId, account: &T::AccountId) -> T::Balance {
+        match Self::vesting((token_id, account.clone())) {
+            Some(schedule) => {
+                let now = <system::Module<T>>::block_number();
+                let elapsed: u32 = now.saturating_sub(schedule.starting_block).saturated_into();
+                let unlocked = schedule.per_block.saturating_mul(T::Balance::from(elapsed));
+                schedule.locked.saturating_sub(unlocked)
+            }
+            None => T::Balance::zero(),
+        }
+    }
+    /// account holding the native currency of the wrapped tokens
+    pub fn wrap_account() -> T::AccountId {
+        WRAP_MODULE_ID.into_account()
+    }
+    /// account holding the distributed tokens until they are claimed
+    pub fn distribution_account() -> T::AccountId {
+        DISTRIBUTION_MODULE_ID.into_account()
+    }
+    pub fn _create_snapshot(token_id: TokenId) -> Result<SnapshotId> {
+        Self::check_token_exists(token_id)?;
+        let id = Self::snapshot_count(token_id)
+            .checked_add(1)
+            .ok_or("Overflow adding new snapshot")?;
+        let now = <system::Module<T>>::block_number();
+
+        <SnapshotCount>::insert(token_id, id);
+        <SnapshotBlocks<T>>::insert(token_id, id, now);
+        Self::deposit_event(RawEvent::SnapshotCreated(token_id, id, now));
+        Ok(id)
+    }
+    pub fn balance_at(token_id: TokenId, account: &T::AccountId, snapshot_id: SnapshotId) -> Result<T::Balance> {
+        Self::check_snapshot(token_id, snapshot_id)?;
+        let key = (token_id, account.clone());
+        Ok(Self::value_at(&Self::balance_snapshots(&key), snapshot_id, Self::balance_of(&key)))
+    }
+    pub fn total_supply_at(token_id: TokenId, snapshot_id: SnapshotId) -> Result<T::Balance> {
+        Self::check_snapshot(token_id, snapshot_id)?;
+        Ok(Self::value_at(&Self::supply_snapshots(token_id), snapshot_id, Self::total_supply(token_id)))
+    }
+    fn check_snapshot(token_id: TokenId, snapshot_id: SnapshotId) -> Result<()> {
+        ensure!(
+            <SnapshotBlocks<T>>::contains_key(token_id, snapshot_id),
+            "The snapshot does not exist"
+        );
+        Ok(())
+    }
+    /// the first value recorded at or after the snapshot is the one the snapshot saw
+    fn value_at(snapshots: &[(SnapshotId, T::Balance)], snapshot_id: SnapshotId, current: T::Balance) -> T::Balance {
+        match snapshots.binary_search_by_key(&snapshot_id, |(id, _)| *id) {
+            Ok(i) => snapshots[i].1,
+            Err(i) => snapshots.get(i).map_or(current, |(_, value)| *value),
+        }
+    }
+    /// the only place the balance is written, keeping the snapshots and the holders up to date
+    fn set_balance(token_id: TokenId, account: &T::AccountId, balance: T::Balance) {
+        Self::snapshot_balance(token_id, account);
+        let key = (token_id, account.clone());
+        match (Self::balance_of(&key).is_zero(), balance.is_zero()) {
+            (true, false) => Self::add_holder(token_id, account),
+            (false, true) => Self::remove_holder(token_id, account),
+            _ => (),
+        }
+        match balance.is_zero() {
+            true => <Balance<T>>::remove(&key),
+            false => <Balance<T>>::insert(&key, balance),
+        }
+    }
+    fn add_holder(token_id: TokenId, account: &T::AccountId) {
+        let count = Self::holder_count(token_id);
+        <Holders<T>>::insert(token_id, count, account);
+        <HolderIndex<T>>::insert(token_id, account, count);
+        <HolderCount>::insert(token_id, count.saturating_add(1));
+    }
+    // the last holder takes the index of the removed one
+    fn remove_holder(token_id: TokenId, account: &T::AccountId) {
+        if let Some(index) = <HolderIndex<T>>::take(token_id, account) {
+            let last = Self::holder_count(token_id) - 1;
+            if index != last {
+                if let Some(moved) = <Holders<T>>::get(token_id, last) {
+                    <Holders<T>>::insert(token_id, index, &moved);
+                    <HolderIndex<T>>::insert(token_id, &moved, index);
+                }
+            }
+            <Holders<T>>::remove(token_id, last);
+            <HolderCount>::insert(token_id, last);
+        }
+    }
+    /// decimals set by the admin for display, the decimals of the token otherwise
+    pub fn display_decimals(token_id: TokenId) -> u16 {
+        Self::display_decimals_override(token_id).unwrap_or_else(|| Self::token_map(token_id).decimals)
+    }
+    pub fn account_tokens(account: T::AccountId) -> Vec<(TokenId, T::Balance)> {
+        Self::tokens()
+            .into_iter()
+            .map(|token| (token.id, Self::balance_of((token.id, account.clone()))))
+            .filter(|(_, balance)| !balance.is_zero())
+            .collect()
+    }
+    /// page of the holders starting from the index, at most MAX_HOLDERS_PAGE of them
+    pub fn holders(token_id: TokenId, start: u32, limit: u32) -> Vec<T::AccountId> {
+        let end = start
+            .saturating_add(limit.min(MAX_HOLDERS_PAGE))
+            .min(Self::holder_count(token_id));
+        (start..end).filter_map(|index| Self::holder(token_id, index)).collect()
+    }
+    // copy the balance on its first change after the last snapshot
+    fn snapshot_balance(token_id: TokenId, account: &T::AccountId) {
+        let current = Self::snapshot_count(token_id);
+        let key = (token_id, account.clone());
+        let mut snapshots = Self::balance_snapshots(&key);
+        if current > snapshots.last().map_or(0, |(id, _)| *id) {
+            snapshots.push((current, Self::balance_of(&key)));
+            <BalanceSnapshots<T>>::insert(&key, snapshots);
+        }
+    }
+    fn snapshot_supply(token_id: TokenId) {
+        let current = Self::snapshot_count(token_id);
+        let mut snapshots = Self::supply_snapshots(token_id);
+        if current > snapshots.last().map_or(0, |(id, _)| *id) {
+            snapshots.push((current, Self::total_supply(token_id)));
+            <SupplySnapshots<T>>::insert(token_id, snapshots);
+        }
+    }
+    /// balance which is neither locked nor reserved, the named locks overlap and the bridge lock comes on top of them
+    pub fn usable_balance(token_id: TokenId, account: &T::AccountId) -> T::Balance {
+        let named = Self::locks((token_id, account.clone()))
+            .into_iter()
+            .map(|(_, amount)| amount)
+            .fold(T::Balance::zero(), |max, amount| max.max(amount));
+        let locked = Self::locked((token_id, account.clone())).saturating_add(named);
+        Self::balance_of((token_id, account.clone())).saturating_sub(locked)
+    }
+    /// create or change the lock, it doesn't stack with other locks of the account
+    pub fn set_lock(id: LockIdentifier, token_id: TokenId, account: &T::AccountId, amount: T::Balance) {
+        let key = (token_id, account.clone());
+        let mut locks: Vec<_> = Self::locks(&key).into_iter().filter(|(lock, _)| *lock != id).collect();
+        if !amount.is_zero() {
+            locks.push((id, amount));
+        }
+        match locks.is_empty() {
+            true => <Locks<T>>::remove(&key),
+            false => {
+                Self::track_account(token_id, account);
+                <Locks<T>>::insert(&key, locks)
+            }
+        }
+    }
+    fn track_account(token_id: TokenId, account: &T::AccountId) {
+        <TokenAccounts<T>>::mutate(token_id, |accounts| {
+            if !accounts.contains(account) {
+                accounts.push(account.clone());
+            }
+        });
+    }
+    pub fn remove_lock(id: LockIdentifier, token_id: TokenId, account: &T::AccountId) {
+        Self::set_lock(id, token_id, account, T::Balance::zero());
+    }
+    /// move usable tokens of the account to its reserved balance
+    pub fn reserve(token_id: TokenId, account: &T::AccountId, amount: T::Balance) -> TokenResult<(), T> {
+        ensure!(!Self::is_frozen(token_id, account), Error::<T>::AccountFrozen);
+        ensure!(Self::usable_balance(token_id, account) >= amount, Error::<T>::InsufficientBalance);
+        let key = (token_id, account.clone());
+        let reserved = Self::reserved_balance(&key)
+            .checked_add(&amount)
+            .ok_or(Error::<T>::Overflow)?;
+        let balance = Self::balance_of(&key)
+            .checked_sub(&amount)
+            .ok_or(Error::<T>::InsufficientBalance)?;
+
+        Self::set_balance(token_id, account, balance);
+        <Reserved<T>>::insert(&key, reserved);
+        Ok(())
+    }
+    /// move reserved tokens back to the balance, returns the amount which was not reserved
+    pub fn unreserve(token_id: TokenId, account: &T::AccountId, amount: T::Balance) -> T::Balance {
+        let key = (token_id, account.clone());
+        let reserved = Self::reserved_balance(&key);
+        let actual = amount.min(reserved);
+
+        match reserved - actual {
+            left if left.is_zero() => <Reserved<T>>::remove(&key),
+            left => <Reserved<T>>::insert(&key, left),
+        }
+        // reserved tokens are counted in the total supply, so the balance can't overflow
+        Self::set_balance(token_id, account, Self::balance_of(&key).saturating_add(actual));
+        amount - actual
+    }
+    /// second phase of a burn whose tokens were reserved in the first one
+    pub fn burn_reserved(token_id: TokenId, account: &T::AccountId, amount: T::Balance) -> TokenResult<(), T> {
+        let key = (token_id, account.clone());
+        let left = Self::reserved_balance(&key)
+            .checked_sub(&amount)
+            .ok_or(Error::<T>::InsufficientReserved)?;
+        let next_total = Self::total_supply(token_id)
+            .checked_sub(&amount)
+            .ok_or(Error::<T>::Underflow)?;
+
+        match left.is_zero() {
+            true => <Reserved<T>>::remove(&key),
+            false => <Reserved<T>>::insert(&key, left),
+        }
+        Self::snapshot_supply(token_id);
+        <TotalSupply<T>>::insert(token_id, next_total);
+        T::OnTransfer::on_burn(token_id, account, amount);
+        Ok(())
+    }
+    // Token management
+    /// register new token under the next id
+    pub fn _create_token(symbol: Vec<u8>, decimals: u16) -> Result<TokenId> {
+        Self::check_symbol_is_free(&symbol)?;
+        let id = <Count>::get();
+        let next = id.checked_add(1).ok_or("Overflow adding new token")?;
+        let token = Token {
+            id,
+            decimals,
+            symbol: symbol.clone(),
+        };
+        <Tokens>::mutate(|t| t.push(token.clone()));
+        <TokenMap>::insert(id, token);
+        <TokenIds>::insert(symbol.clone(), id);
+        <TokenSymbol>::insert(id, symbol);
+        <Count>::put(next);
+        Ok(id)
+    }
+    /// remove the token with no supply left, its id is not reused
+    pub fn _destroy_token(token_id: TokenId) -> Result<()> {
+        Self::check_token_exists(token_id)?;
+        ensure!(Self::total_supply(token_id).is_zero(), "The token still has supply");
+        ensure!(Self::wrapped_native() != Some(token_id), "The wrapped native token can not be destroyed");
+
+        if let Some((creator, deposit)) = <TokenCreators<T>>::take(token_id) {
+            <balances::Module<T> as ReservableCurrency<_>>::unreserve(&creator, deposit);
+        }
+        if let Some(symbol) = <TokenSymbol>::take(token_id) {
+            <TokenIds>::remove(symbol);
+        }
+        <Tokens>::mutate(|tokens| tokens.retain(|t| t.id != token_id));
+        <TokenMap>::remove(token_id);
+        <TokenNames>::remove(token_id);
+        <TokenMetadata>::remove(token_id);
+        <DisplayDecimals>::remove(token_id);
+        <MetadataFrozen>::remove(token_id);
+        <TotalSupply<T>>::remove(token_id);
+        <TokenAdmins<T>>::remove(token_id);
+        <MaxSupply<T>>::remove(token_id);
+        <MinimumBalance<T>>::remove(token_id);
+        <MaxHolders>::remove(token_id);
+        <TransferFees<T>>::remove(token_id);
+        <PausedTokens>::remove(token_id);
+        <Soulbound>::remove(token_id);
+        <WhitelistOnly>::remove(token_id);
+        <SnapshotCount>::remove(token_id);
+        <SupplySnapshots<T>>::remove(token_id);
+        <TokenRoles<T>>::remove_prefix(token_id);
+        <RoleHolders>::remove((token_id, TokenRole::Minter));
+        <RoleHolders>::remove((token_id, TokenRole::Burner));
+        <FrozenAccounts<T>>::remove_prefix(token_id);
+        <Whitelist<T>>::remove_prefix(token_id);
+        <MaxHoldersExempt<T>>::remove_prefix(token_id);
+        <SnapshotBlocks<T>>::remove_prefix(token_id);
+        for account in <TokenAccounts<T>>::take(token_id) {
+            <Allowances<T>>::remove_prefix((token_id, account.clone()));
+            <Locked<T>>::remove((token_id, account.clone()));
+            <Locks<T>>::remove((token_id, account.clone()));
+            <Vesting<T>>::remove((token_id, account));
+        }
+
+        Self::deposit_event(RawEvent::TokenDestroyed(token_id));
+        Ok(())
+    }
+    pub fn check_token_exists(token_id: TokenId) -> TokenResult<(), T> {
+        ensure!(<TokenSymbol>::contains_key(token_id), Error::<T>::TokenNotFound);
+        Ok(())
+    }
+    pub fn check_symbol_is_free(symbol: &[u8]) -> Result<()> {
+        Self::validate_name(symbol)?;
+        ensure!(!<TokenIds>::contains_key(symbol), "The token symbol is already used");
+        Ok(())
+    }
+
+    fn validate_name(name: &[u8]) -> Result<()> {
+        if name.len() > 10 {
+            return Err("The token symbol is too long");
+        }
+        if name.len() < 3 {
+            return Err("The token symbol is too short");
+        }
+
+        Ok(())
+    }
+}
+
+/// tests for this module
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frame_support::{
+        assert_noop, assert_ok, impl_outer_origin, parameter_types, traits::Get, weights::Weight,
+    };
+    use sp_core::H256;
+    use sp_runtime::{
+        testing::{Header, TestSignature, UintAuthorityId},
+        traits::{BlakeTwo256, IdentityLookup},
+        Perbill,
+    };
+    use std::cell::RefCell;
+
+    pub type Balance = u128;
+
+    thread_local! {
+        static EXISTENTIAL_DEPOSIT: RefCell<u128> = RefCell::new(500);
+        static HOOK_CALLS: RefCell<Vec<(&'static str, TokenId, Balance)>> = RefCell::new(vec![]);
+    }
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+    pub struct ExistentialDeposit;
+    impl Get<u128> for ExistentialDeposit {
+        fn get() -> u128 {
+            EXISTENTIAL_DEPOSIT.with(|v| *v.borrow())
+        }
+    }
+    impl Get<u64> for ExistentialDeposit {
+        fn get() -> u64 {
+            EXISTENTIAL_DEPOSIT.with(|v| *v.borrow() as u64)
+        }
+    }
+
+    // For testing the module, we construct most of a mock runtime. This means
+    // first constructing a configuration type (`Test`) which `impl`s each of the
+    // configuration traits of modules we want to use.
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+        type ModuleToIndex = ();
+        type AccountData = balances::AccountData<u128>;
+        type OnNewAccount = ();
+        type OnKilledAccount = ();
+    }
+
+    impl balances::Trait for Test {
+        type Balance = Balance;
+        type DustRemoval = ();
+        type Event = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type AccountStore = system::Module<Test>;
+    }
+
+    parameter_types! {
         pub const MinimumPeriod: u64 = 5;
     }
-    impl timestamp::Trait for Test {
-        type Moment = u64;
-        type OnTimestampSet = ();
-        type MinimumPeriod = MinimumPeriod;
+    impl timestamp::Trait for Test {
+        type Moment = u64;
+        type OnTimestampSet = ();
+        type MinimumPeriod = MinimumPeriod;
+    }
+    pub struct TestTransferHook;
+    impl OnTokenTransfer<u64, Balance> for TestTransferHook {
+        fn on_transfer(token_id: TokenId, _from: &u64, _to: &u64, amount: Balance) {
+            HOOK_CALLS.with(|v| v.borrow_mut().push(("transfer", token_id, amount)));
+        }
+        fn on_mint(token_id: TokenId, _to: &u64, amount: Balance) {
+            HOOK_CALLS.with(|v| v.borrow_mut().push(("mint", token_id, amount)));
+        }
+        fn on_burn(token_id: TokenId, _from: &u64, amount: Balance) {
+            HOOK_CALLS.with(|v| v.borrow_mut().push(("burn", token_id, amount)));
+        }
+    }
+    impl Trait for Test {
+        type Event = ();
+        type OnTransfer = TestTransferHook;
+        type Signature = TestSignature;
+        type Signer = UintAuthorityId;
+    }
+
+    type TokenModule = Module<Test>;
+
+    const TOKEN_NAME: &[u8; 4] = b"DOOM";
+    const TOKEN_SHORT_NAME: &[u8; 1] = b"T";
+    const TOKEN_LONG_NAME: &[u8; 34] = b"nobody_really_want_such_long_token";
+    const USER1: u64 = 1;
+    const USER2: u64 = 2;
+    const TOKEN_ID: u32 = 0;
+
+    pub struct ExtBuilder {
+        existential_deposit: u128,
+    }
+
+    impl Default for ExtBuilder {
+        fn default() -> Self {
+            Self {
+                existential_deposit: 500,
+            }
+        }
+    }
+
+    impl ExtBuilder {
+        pub fn set_associated_consts(&self) {
+            EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = self.existential_deposit);
+        }
+        pub fn build(self) -> sp_io::TestExternalities {
+            self.set_associated_consts();
+            let mut storage = system::GenesisConfig::default()
+                .build_storage::<Test>()
+                .unwrap();
+
+            let _ = balances::GenesisConfig::<Test> {
+                balances: vec![(USER1, 100000), (USER2, 300000)],
+            }
+            .assimilate_storage(&mut storage);
+            let _ = GenesisConfig::<Test> {
+                tokens: vec![Token {
+                    id: 0,
+                    decimals: 18,
+                    symbol: TOKEN_NAME.to_vec(),
+                }],
+                max_supplies: vec![],
+                balances: vec![],
+            }
+            .assimilate_storage(&mut storage);
+
+            let ext = sp_io::TestExternalities::from(storage);
+            ext
+        }
+    }
+
+    #[test]
+    fn new_token_mint_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::check_token_exists(TOKEN_ID));
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER2, 1000));
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, USER2)), 1000);
+            assert_eq!(TokenModule::total_supply(TOKEN_ID), 1000);
+        })
+    }
+
+    #[test]
+    fn create_token_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_eq!(TokenModule::_create_token(b"USDT".to_vec(), 6), Ok(1));
+            assert_eq!(TokenModule::count(), 2);
+            assert_eq!(TokenModule::token_id_by_symbol(b"USDT".to_vec()), Some(1));
+            assert_eq!(TokenModule::token_map(1).decimals, 6);
+            assert_eq!(
+                TokenModule::_create_token(TOKEN_NAME.to_vec(), 18),
+                Err("The token symbol is already used")
+            );
+            assert_eq!(
+                TokenModule::_create_token(TOKEN_SHORT_NAME.to_vec(), 18),
+                Err("The token symbol is too short")
+            );
+        })
+    }
+    #[test]
+    fn snapshots_work() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::create_token(Origin::signed(USER1), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 1000, None));
+            assert_noop!(
+                TokenModule::create_snapshot(Origin::signed(USER2), 1),
+                "Only the token admin can manage the token"
+            );
+            assert_eq!(TokenModule::balance_at(1, &USER1, 1), Err("The snapshot does not exist"));
+
+            assert_ok!(TokenModule::create_snapshot(Origin::signed(USER1), 1));
+            assert_ok!(TokenModule::transfer(Origin::signed(USER1), USER2, 1, 300));
+            assert_ok!(TokenModule::_mint(1, USER2, 500));
+            assert_ok!(TokenModule::create_snapshot(Origin::signed(USER1), 1));
+            assert_ok!(TokenModule::create_snapshot(Origin::signed(USER1), 1));
+            assert_ok!(TokenModule::transfer(Origin::signed(USER2), USER1, 1, 100));
+
+            assert_eq!(TokenModule::balance_at(1, &USER1, 1), Ok(1000));
+            assert_eq!(TokenModule::balance_at(1, &USER2, 1), Ok(0));
+            assert_eq!(TokenModule::total_supply_at(1, 1), Ok(1000));
+            assert_eq!(TokenModule::balance_at(1, &USER1, 2), Ok(700));
+            assert_eq!(TokenModule::balance_at(1, &USER2, 3), Ok(800));
+            assert_eq!(TokenModule::total_supply_at(1, 3), Ok(1500));
+            // transfers after the snapshot do not change it
+            assert_eq!(TokenModule::balance_of((1, USER2)), 700);
+        })
+    }
+    #[test]
+    fn distribution_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::create_token(Origin::signed(USER1), b"SHARE".to_vec(), b"Shares".to_vec(), 0, 1000, None));
+            assert_ok!(TokenModule::transfer(Origin::signed(USER1), USER2, 1, 250));
+            assert_ok!(TokenModule::_mint(TOKEN_ID, 3, 100));
+
+            assert_ok!(TokenModule::distribute(Origin::signed(3), 1, TOKEN_ID, 100));
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, TokenModule::distribution_account())), 100);
+            // transfers after the distribution do not change the shares
+            assert_ok!(TokenModule::transfer(Origin::signed(USER1), USER2, 1, 750));
+
+            assert_ok!(TokenModule::claim_distribution(Origin::signed(USER2), 0));
+            assert_ok!(TokenModule::claim_distribution(Origin::signed(USER1), 0));
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, USER2)), 25);
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, USER1)), 75);
+            assert_noop!(
+                TokenModule::claim_distribution(Origin::signed(USER2), 0),
+                "The distribution is already claimed"
+            );
+            assert_noop!(TokenModule::claim_distribution(Origin::signed(3), 0), "Nothing to claim");
+            assert_noop!(
+                TokenModule::claim_distribution(Origin::signed(3), 1),
+                "The distribution does not exist"
+            );
+
+            // the holding of the distributor is left out of the shares
+            assert_noop!(
+                TokenModule::distribute(Origin::signed(USER1), 1, 1, 10),
+                "The token can not be distributed to its own holders"
+            );
+            assert_ok!(TokenModule::transfer(Origin::signed(USER2), USER1, 1, 600));
+            assert_ok!(TokenModule::distribute(Origin::signed(USER1), 1, TOKEN_ID, 40));
+            assert_ok!(TokenModule::claim_distribution(Origin::signed(USER2), 1));
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, USER2)), 65);
+            assert_noop!(
+                TokenModule::claim_distribution(Origin::signed(USER1), 1),
+                "The distribution is already claimed"
+            );
+        })
+    }
+    #[test]
+    fn token_registry_lookups_work() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_eq!(TokenModule::token_id_by_symbol(TOKEN_NAME.to_vec()), Some(TOKEN_ID));
+            assert_eq!(TokenModule::symbol_by_token_id(TOKEN_ID), Some(TOKEN_NAME.to_vec()));
+            assert_eq!(TokenModule::token_id_by_symbol(b"NONE".to_vec()), None);
+            assert_eq!(TokenModule::symbol_by_token_id(1), None);
+            assert_eq!(TokenModule::check_token_exists(1), Err(Error::<Test>::TokenNotFound));
+            assert_noop!(
+                TokenModule::mint(Origin::signed(USER1), USER2, 1, 100),
+                Error::<Test>::TokenNotFound
+            );
+
+            assert_eq!(TokenModule::_create_token(b"USDT".to_vec(), 6), Ok(1));
+            assert_eq!(TokenModule::symbol_by_token_id(1), Some(b"USDT".to_vec()));
+            assert_ok!(TokenModule::check_token_exists(1));
+        })
+    }
+    #[test]
+    fn create_token_extrinsic_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::set_creation_deposit(Origin::ROOT, 1000));
+            assert_ok!(TokenModule::create_token(
+                Origin::signed(USER1),
+                b"USDC".to_vec(),
+                b"USD Coin".to_vec(),
+                6,
+                5000,
+                None
+            ));
+            assert_eq!(TokenModule::token_id_by_symbol(b"USDC".to_vec()), Some(1));
+            assert_eq!(TokenModule::token_name(1), b"USD Coin".to_vec());
+            assert_eq!(TokenModule::token_map(1).decimals, 6);
+            assert_eq!(TokenModule::balance_of((1, USER1)), 5000);
+            assert_eq!(TokenModule::total_supply(1), 5000);
+            assert_eq!(TokenModule::token_creator(1), Some((USER1, 1000)));
+            assert_eq!(balances::Module::<Test>::reserved_balance(&USER1), 1000);
+
+            assert_noop!(
+                TokenModule::create_token(Origin::signed(USER2), b"USDC".to_vec(), b"Copy".to_vec(), 6, 0, None),
+                "The token symbol is already used"
+            );
+            assert_noop!(
+                TokenModule::create_token(Origin::signed(USER2), b"EMPTY".to_vec(), vec![], 6, 0, None),
+                "The token name is empty"
+            );
+        })
+    }
+    #[test]
+    fn transfer_fee_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            const FEE_ACCOUNT: u64 = 3;
+            assert_ok!(TokenModule::create_token(Origin::signed(USER1), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 1000, None));
+            assert_noop!(
+                TokenModule::set_transfer_fee(Origin::signed(USER2), 1, Some((TransferFee::Flat(5), FEE_ACCOUNT))),
+                "Only the token admin can manage the token"
+            );
+
+            assert_ok!(TokenModule::set_transfer_fee(Origin::signed(USER1), 1, Some((TransferFee::Flat(5), FEE_ACCOUNT))));
+            assert_ok!(TokenModule::transfer(Origin::signed(USER1), USER2, 1, 100));
+            assert_eq!(TokenModule::balance_of((1, USER1)), 900);
+            assert_eq!(TokenModule::balance_of((1, USER2)), 95);
+            assert_eq!(TokenModule::balance_of((1, FEE_ACCOUNT)), 5);
+            assert_noop!(
+                TokenModule::transfer(Origin::signed(USER1), USER2, 1, 5),
+                Error::<Test>::FeeNotCovered
+            );
+
+            // 2.5% of 99 leaves 97 to the recipient
+            let share = Perbill::from_parts(25_000_000);
+            assert_ok!(TokenModule::set_transfer_fee(Origin::signed(USER1), 1, Some((TransferFee::Proportional(share), FEE_ACCOUNT))));
+            assert_ok!(TokenModule::transfer(Origin::signed(USER1), USER2, 1, 99));
+            assert_eq!(TokenModule::balance_of((1, USER2)), 95 + 97);
+            assert_eq!(TokenModule::balance_of((1, FEE_ACCOUNT)), 7);
+
+            assert_ok!(TokenModule::set_transfer_fee(Origin::signed(USER1), 1, None));
+            assert_ok!(TokenModule::transfer(Origin::signed(USER1), USER2, 1, 10));
+            assert_eq!(TokenModule::balance_of((1, FEE_ACCOUNT)), 7);
+        })
+    }
+    #[test]
+    fn transfer_hook_is_called() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER1, 1000));
+            assert_ok!(TokenModule::transfer(Origin::signed(USER1), USER2, TOKEN_ID, 300));
+            assert_ok!(TokenModule::burn(Origin::signed(USER2), TOKEN_ID, 100, None));
+            assert_noop!(
+                TokenModule::transfer(Origin::signed(USER2), USER1, TOKEN_ID, 300),
+                Error::<Test>::InsufficientBalance
+            );
+            assert_eq!(
+                HOOK_CALLS.with(|v| v.borrow().clone()),
+                vec![("mint", TOKEN_ID, 1000), ("transfer", TOKEN_ID, 300), ("burn", TOKEN_ID, 100)]
+            );
+        })
+    }
+    #[test]
+    fn vesting_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            let schedule = VestingSchedule {
+                locked: 100,
+                per_block: 10,
+                starting_block: 5,
+            };
+            assert_ok!(TokenModule::create_token(Origin::signed(USER1), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 0, None));
+            assert_noop!(
+                TokenModule::mint_vested(Origin::signed(USER2), USER2, 1, schedule),
+                Error::<Test>::NotMinter
+            );
+            assert_ok!(TokenModule::grant_role(Origin::signed(USER1), 1, TokenRole::Minter, USER1));
+            assert_ok!(TokenModule::mint_vested(Origin::signed(USER1), USER2, 1, schedule));
+            assert_noop!(
+                TokenModule::mint_vested(Origin::signed(USER1), USER2, 1, schedule),
+                "The account already has a vesting schedule"
+            );
+            assert_eq!(TokenModule::balance_of((1, USER2)), 100);
+            assert_eq!(TokenModule::usable_balance(1, &USER2), 0);
+
+            // nothing is unlocked before the cliff
+            system::Module::<Test>::set_block_number(4);
+            assert_ok!(TokenModule::claim_vested(Origin::signed(USER2), 1));
+            assert_eq!(TokenModule::usable_balance(1, &USER2), 0);
+
+            system::Module::<Test>::set_block_number(8);
+            assert_ok!(TokenModule::claim_vested(Origin::signed(USER2), 1));
+            assert_eq!(TokenModule::usable_balance(1, &USER2), 30);
+            assert_ok!(TokenModule::transfer(Origin::signed(USER2), USER1, 1, 30));
+
+            system::Module::<Test>::set_block_number(20);
+            assert_ok!(TokenModule::claim_vested(Origin::signed(USER2), 1));
+            assert_eq!(TokenModule::vesting((1, USER2)), None);
+            assert_eq!(TokenModule::usable_balance(1, &USER2), 70);
+        })
+    }
+    #[test]
+    fn reserve_and_named_locks_work() {
+        ExtBuilder::default().build().execute_with(|| {
+            const STAKING: LockIdentifier = *b"staking ";
+            const DAO: LockIdentifier = *b"dao     ";
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER1, 1000));
+
+            assert_ok!(TokenModule::reserve(TOKEN_ID, &USER1, 400));
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, USER1)), 600);
+            assert_eq!(TokenModule::reserved_balance((TOKEN_ID, USER1)), 400);
+            assert_eq!(TokenModule::total_supply(TOKEN_ID), 1000);
+            assert_eq!(
+                TokenModule::reserve(TOKEN_ID, &USER1, 601),
+                Err(Error::<Test>::InsufficientBalance)
+            );
+
+            // locks overlap instead of stacking
+            TokenModule::set_lock(STAKING, TOKEN_ID, &USER1, 300);
+            TokenModule::set_lock(DAO, TOKEN_ID, &USER1, 500);
+            assert_eq!(TokenModule::usable_balance(TOKEN_ID, &USER1), 100);
+            assert_noop!(
+                TokenModule::transfer(Origin::signed(USER1), USER2, TOKEN_ID, 101),
+                Error::<Test>::LockedFunds
+            );
+            TokenModule::remove_lock(DAO, TOKEN_ID, &USER1);
+            assert_eq!(TokenModule::usable_balance(TOKEN_ID, &USER1), 300);
+
+            assert_eq!(TokenModule::unreserve(TOKEN_ID, &USER1, 500), 100);
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, USER1)), 1000);
+            assert_eq!(TokenModule::reserved_balance((TOKEN_ID, USER1)), 0);
+        })
+    }
+    #[test]
+    fn burn_reserved_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER1, 1000));
+            assert_ok!(TokenModule::reserve(TOKEN_ID, &USER1, 400));
+
+            assert_eq!(TokenModule::burn_reserved(TOKEN_ID, &USER1, 401), Err(Error::<Test>::InsufficientReserved));
+            assert_ok!(TokenModule::burn_reserved(TOKEN_ID, &USER1, 300));
+            assert_eq!(TokenModule::reserved_balance((TOKEN_ID, USER1)), 100);
+            assert_eq!(TokenModule::total_supply(TOKEN_ID), 700);
+
+            // the rest of a cancelled burn goes back to the balance
+            assert_eq!(TokenModule::unreserve(TOKEN_ID, &USER1, 100), 0);
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, USER1)), 700);
+        })
+    }
+    #[test]
+    fn max_holders_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::create_token(Origin::signed(USER1), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 1000, None));
+            assert_noop!(
+                TokenModule::set_max_holders(Origin::signed(USER2), 1, Some(2)),
+                "Only the token admin can manage the token"
+            );
+            assert_ok!(TokenModule::set_max_holders(Origin::signed(USER1), 1, Some(2)));
+
+            assert_ok!(TokenModule::transfer(Origin::signed(USER1), USER2, 1, 400));
+            assert_noop!(
+                TokenModule::transfer(Origin::signed(USER1), 3, 1, 100),
+                Error::<Test>::MaxHoldersExceeded
+            );
+            assert_eq!(TokenModule::_mint(1, 3, 100), Err(Error::<Test>::MaxHoldersExceeded));
+            // existing holders and leaving senders are not limited
+            assert_ok!(TokenModule::transfer(Origin::signed(USER1), USER2, 1, 100));
+            assert_ok!(TokenModule::transfer(Origin::signed(USER2), 3, 1, 500));
+            assert_eq!(TokenModule::holder_count(1), 2);
+
+            // the admin lets an account in over the limit
+            assert_noop!(
+                TokenModule::set_max_holders_exempt(Origin::signed(USER2), 1, 5, true),
+                "Only the token admin can manage the token"
+            );
+            assert_ok!(TokenModule::set_max_holders_exempt(Origin::signed(USER1), 1, 5, true));
+            assert_ok!(TokenModule::transfer(Origin::signed(USER1), 5, 1, 100));
+            assert_eq!(TokenModule::holder_count(1), 3);
+            assert_ok!(TokenModule::set_max_holders_exempt(Origin::signed(USER1), 1, 5, false));
+            assert!(!TokenModule::is_max_holders_exempt(1, 5));
+
+            assert_ok!(TokenModule::set_max_holders(Origin::signed(USER1), 1, None));
+            assert_ok!(TokenModule::transfer(Origin::signed(USER1), 4, 1, 100));
+            assert_eq!(TokenModule::holder_count(1), 4);
+        })
+    }
+    #[test]
+    fn keep_alive_transfers_work() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::create_token(Origin::signed(USER1), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 1000, None));
+            assert_noop!(
+                TokenModule::transfer_keep_alive(Origin::signed(USER1), USER2, 1, 1000),
+                "The transfer would reap the sender account"
+            );
+            assert_ok!(TokenModule::transfer_keep_alive(Origin::signed(USER1), USER2, 1, 900));
+            assert_eq!(TokenModule::balance_of((1, USER1)), 100);
+
+            assert_ok!(TokenModule::set_minimum_balance(Origin::signed(USER1), 1, 10));
+            assert_ok!(TokenModule::transfer_all(Origin::signed(USER1), 1, USER2, true));
+            assert_eq!(TokenModule::balance_of((1, USER1)), 10);
+            assert_noop!(
+                TokenModule::transfer_all(Origin::signed(USER1), 1, USER2, true),
+                "Nothing to transfer"
+            );
+            assert_ok!(TokenModule::transfer_all(Origin::signed(USER1), 1, USER2, false));
+            assert_eq!(TokenModule::balance_of((1, USER1)), 0);
+            assert_eq!(TokenModule::balance_of((1, USER2)), 1000);
+        })
+    }
+    #[test]
+    fn transfer_with_memo_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER1, 1000));
+            assert_noop!(
+                TokenModule::transfer_with_memo(Origin::signed(USER1), TOKEN_ID, USER2, 100, vec![0; 129]),
+                Error::<Test>::MemoTooLong
+            );
+            assert_ok!(TokenModule::transfer_with_memo(
+                Origin::signed(USER1),
+                TOKEN_ID,
+                USER2,
+                100,
+                b"invoice 42".to_vec()
+            ));
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, USER2)), 100);
+        })
+    }
+    #[test]
+    fn transfer_batch_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER1, 1000));
+            assert_noop!(
+                TokenModule::transfer_batch(Origin::signed(USER1), TOKEN_ID, vec![(USER2, 600), (3, 600)]),
+                Error::<Test>::InsufficientBalance
+            );
+            assert_noop!(
+                TokenModule::transfer_batch(Origin::signed(USER1), TOKEN_ID, vec![(USER2, 600), (3, 0)]),
+                "Transfer Amount should be non-zero"
+            );
+            assert_noop!(
+                TokenModule::transfer_batch(Origin::signed(USER1), TOKEN_ID, vec![]),
+                "The batch is empty"
+            );
+
+            assert_ok!(TokenModule::transfer_batch(
+                Origin::signed(USER1),
+                TOKEN_ID,
+                vec![(USER2, 600), (3, 300)]
+            ));
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, USER1)), 100);
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, USER2)), 600);
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, 3)), 300);
+        })
+    }
+    #[test]
+    fn max_supply_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_noop!(
+                TokenModule::create_token(Origin::signed(USER1), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 1001, Some(1000)),
+                Error::<Test>::MaxSupplyExceeded
+            );
+            assert_ok!(TokenModule::create_token(Origin::signed(USER1), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 900, Some(1000)));
+            assert_eq!(TokenModule::max_supply(1), Some(1000));
+
+            assert_noop!(TokenModule::_mint(1, USER2, 101), Error::<Test>::MaxSupplyExceeded);
+            assert_ok!(TokenModule::_mint(1, USER2, 100));
+            assert_eq!(TokenModule::total_supply(1), 1000);
+            // burned tokens can be minted again
+            assert_ok!(TokenModule::_burn(1, USER2, 50));
+            assert_ok!(TokenModule::_mint(1, USER2, 50));
+            // tokens without the cap are not limited
+            assert_eq!(TokenModule::max_supply(TOKEN_ID), None);
+        })
+    }
+    #[test]
+    fn token_roles_work() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::create_token(Origin::signed(USER1), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 0, None));
+            assert_eq!(TokenModule::token_admin(1), Some(USER1));
+            assert_noop!(
+                TokenModule::mint(Origin::signed(USER1), USER2, 1, 100),
+                Error::<Test>::NotMinter
+            );
+            assert_noop!(
+                TokenModule::grant_role(Origin::signed(USER2), 1, TokenRole::Minter, USER2),
+                "Only the token admin can manage the token"
+            );
+
+            assert_ok!(TokenModule::grant_role(Origin::signed(USER1), 1, TokenRole::Minter, USER2));
+            assert_ok!(TokenModule::mint(Origin::signed(USER2), USER2, 1, 100));
+            assert_eq!(TokenModule::balance_of((1, USER2)), 100);
+            assert_noop!(
+                TokenModule::burn_from(Origin::signed(USER2), USER2, 1, 50, None, None),
+                Error::<Test>::NotBurner
+            );
+
+            assert_ok!(TokenModule::revoke_role(Origin::signed(USER1), 1, TokenRole::Minter, USER2));
+            assert!(!TokenModule::has_role(1, (TokenRole::Minter, USER2)));
+            assert_noop!(
+                TokenModule::mint(Origin::signed(USER2), USER2, 1, 100),
+                Error::<Test>::NotMinter
+            );
+        })
+    }
+    #[test]
+    fn freeze_account_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::create_token(Origin::signed(USER1), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 1000, None));
+            assert_noop!(
+                TokenModule::freeze_account(Origin::signed(USER2), 1, USER1),
+                "Only the token admin can manage the token"
+            );
+            assert_ok!(TokenModule::freeze_account(Origin::signed(USER1), 1, USER2));
+            assert!(TokenModule::is_frozen(1, USER2));
+            assert_noop!(
+                TokenModule::transfer(Origin::signed(USER1), USER2, 1, 100),
+                Error::<Test>::RecipientFrozen
+            );
+
+            assert_ok!(TokenModule::thaw_account(Origin::signed(USER1), 1, USER2));
+            assert_ok!(TokenModule::transfer(Origin::signed(USER1), USER2, 1, 100));
+            assert_ok!(TokenModule::freeze_account(Origin::signed(USER1), 1, USER2));
+            assert_noop!(
+                TokenModule::transfer(Origin::signed(USER2), USER1, 1, 100),
+                Error::<Test>::SenderFrozen
+            );
+            // other tokens are not affected
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER2, 100));
+            assert_ok!(TokenModule::transfer(Origin::signed(USER2), USER1, TOKEN_ID, 100));
+        })
+    }
+    #[test]
+    fn wrap_and_unwrap_work() {
+        ExtBuilder::default().build().execute_with(|| {
+            type Balances = balances::Module<Test>;
+            assert_noop!(
+                TokenModule::wrap(Origin::signed(USER1), 1000),
+                "The wrapped native token is not set"
+            );
+            assert_ok!(TokenModule::_create_token(b"WAKT".to_vec(), 18));
+            assert_ok!(TokenModule::create_token(Origin::signed(USER2), b"FAKE".to_vec(), b"Fake".to_vec(), 18, 0, None));
+            assert_noop!(
+                TokenModule::set_wrapped_native(Origin::ROOT, 2),
+                "The wrapped token has an admin or minters"
+            );
+            TokenModule::_grant_role(1, TokenRole::Minter, &USER2);
+            assert_noop!(
+                TokenModule::set_wrapped_native(Origin::ROOT, 1),
+                "The wrapped token has an admin or minters"
+            );
+            TokenModule::_revoke_role(1, TokenRole::Minter, &USER2);
+            assert_ok!(TokenModule::set_wrapped_native(Origin::ROOT, 1));
+
+            assert_ok!(TokenModule::wrap(Origin::signed(USER1), 10000));
+            assert_eq!(Balances::free_balance(USER1), 90000);
+            assert_eq!(Balances::free_balance(TokenModule::wrap_account()), 10000);
+            assert_eq!(TokenModule::balance_of((1, USER1)), 10000);
+
+            // wrapped tokens are unwrapped by any holder
+            assert_ok!(TokenModule::transfer(Origin::signed(USER1), USER2, 1, 4000));
+            assert_ok!(TokenModule::unwrap(Origin::signed(USER2), 4000));
+            assert_eq!(Balances::free_balance(USER2), 304000);
+            assert_eq!(TokenModule::balance_of((1, USER2)), 0);
+            assert_eq!(TokenModule::total_supply(1), 6000);
+            assert_eq!(Balances::free_balance(TokenModule::wrap_account()), 6000);
+
+            // nothing moves on either side when the token side fails
+            <MinimumBalance<Test>>::insert(1, 10);
+            assert_noop!(TokenModule::wrap(Origin::signed(USER2), 5), Error::<Test>::BelowMinimumBalance);
+            assert_noop!(TokenModule::unwrap(Origin::signed(USER1), 5995), Error::<Test>::BelowMinimumBalance);
+        })
+    }
+    #[test]
+    fn permit_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            let genesis_hash = system::Module::<Test>::block_hash(0);
+            let payload = |amount: Balance, deadline: u64, nonce: u32| {
+                (b"permit", genesis_hash, USER1, USER2, TOKEN_ID, amount, deadline, nonce).encode()
+            };
+            let permit = |signature| TokenModule::permit(Origin::signed(3), USER1, USER2, TOKEN_ID, 100, 10, signature);
+
+            // permits signed for another chain are refused
+            let foreign = (b"permit", H256::zero(), USER1, USER2, TOKEN_ID, 100 as Balance, 10u64, 0u32).encode();
+            assert_noop!(permit(TestSignature(USER1, foreign)), "Invalid permit signature");
+
+            assert_noop!(permit(TestSignature(USER2, payload(100, 10, 0))), "Invalid permit signature");
+            assert_noop!(permit(TestSignature(USER1, payload(200, 10, 0))), "Invalid permit signature");
+            assert_ok!(permit(TestSignature(USER1, payload(100, 10, 0))));
+            assert_eq!(TokenModule::allowance_of((TOKEN_ID, USER1), USER2), 100);
+            assert_eq!(TokenModule::permit_nonce(USER1), 1);
+            // the signature can't be replayed
+            assert_noop!(permit(TestSignature(USER1, payload(100, 10, 0))), "Invalid permit signature");
+
+            system::Module::<Test>::set_block_number(11);
+            assert_noop!(permit(TestSignature(USER1, payload(100, 10, 1))), "The permit has expired");
+        })
+    }
+    #[test]
+    fn genesis_balances_work() {
+        let mut storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+        let _ = GenesisConfig::<Test> {
+            tokens: vec![Token {
+                id: 0,
+                decimals: 18,
+                symbol: TOKEN_NAME.to_vec(),
+            }],
+            max_supplies: vec![(TOKEN_ID, 1000)],
+            balances: vec![(TOKEN_ID, USER1, 300), (TOKEN_ID, USER2, 200)],
+        }
+        .assimilate_storage(&mut storage);
+
+        sp_io::TestExternalities::from(storage).execute_with(|| {
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, USER1)), 300);
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, USER2)), 200);
+            assert_eq!(TokenModule::total_supply(TOKEN_ID), 500);
+            assert_eq!(TokenModule::holder_count(TOKEN_ID), 2);
+            assert_eq!(TokenModule::max_supply(TOKEN_ID), Some(1000));
+            assert_eq!(TokenModule::_mint(TOKEN_ID, USER1, 501), Err(Error::<Test>::MaxSupplyExceeded));
+        })
+    }
+    #[test]
+    fn mint_batch_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::create_token(Origin::signed(USER1), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 0, Some(1000)));
+            assert_noop!(
+                TokenModule::mint_batch(Origin::signed(USER1), 1, vec![(USER2, 100)]),
+                Error::<Test>::NotMinter
+            );
+            assert_ok!(TokenModule::grant_role(Origin::signed(USER1), 1, TokenRole::Minter, USER1));
+            assert_noop!(
+                TokenModule::mint_batch(Origin::signed(USER1), 1, vec![(USER2, 600), (3, 600)]),
+                Error::<Test>::MaxSupplyExceeded
+            );
+            assert_noop!(
+                TokenModule::mint_batch(Origin::signed(USER1), 1, vec![(USER2, 600), (3, 0)]),
+                Error::<Test>::ZeroAmount
+            );
+
+            assert_ok!(TokenModule::mint_batch(Origin::signed(USER1), 1, vec![(USER2, 600), (3, 300), (USER2, 100)]));
+            assert_eq!(TokenModule::balance_of((1, USER2)), 700);
+            assert_eq!(TokenModule::balance_of((1, 3)), 300);
+            assert_eq!(TokenModule::total_supply(1), 1000);
+        })
     }
-    impl Trait for Test {
-        type Event = ();
+    #[test]
+    fn minimum_balance_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::create_token(Origin::signed(USER1), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 1000, None));
+            assert_ok!(TokenModule::set_minimum_balance(Origin::signed(USER1), 1, 10));
+
+            assert_noop!(
+                TokenModule::transfer(Origin::signed(USER1), USER2, 1, 5),
+                Error::<Test>::BelowMinimumBalance
+            );
+            assert_noop!(
+                TokenModule::transfer(Origin::signed(USER1), USER2, 1, 995),
+                Error::<Test>::BelowMinimumBalance
+            );
+            assert_eq!(TokenModule::_mint(1, 3, 5), Err(Error::<Test>::BelowMinimumBalance));
+
+            // emptied accounts are reaped
+            assert_ok!(TokenModule::transfer(Origin::signed(USER1), USER2, 1, 1000));
+            assert!(!<Balance<Test>>::contains_key((1, USER1)));
+            assert_eq!(TokenModule::holder_count(1), 1);
+            assert_eq!(TokenModule::_burn(1, USER2, 995), Err(Error::<Test>::BelowMinimumBalance));
+            assert_ok!(TokenModule::_burn(1, USER2, 1000));
+        })
     }
+    #[test]
+    fn later_steps_are_checked_before_the_first_write() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::create_token(Origin::signed(USER1), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 1000, None));
+            assert_ok!(TokenModule::set_minimum_balance(Origin::signed(USER1), 1, 10));
 
-    type TokenModule = Module<Test>;
+            // the second transfer would leave the sender below the minimum
+            assert_noop!(
+                TokenModule::transfer_batch(Origin::signed(USER1), 1, vec![(USER2, 500), (3, 495)]),
+                Error::<Test>::BelowMinimumBalance
+            );
+            // the fee alone would leave the beneficiary below the minimum
+            const FEE_ACCOUNT: u64 = 4;
+            assert_ok!(TokenModule::set_transfer_fee(Origin::signed(USER1), 1, Some((TransferFee::Flat(5), FEE_ACCOUNT))));
+            assert_noop!(
+                TokenModule::transfer(Origin::signed(USER1), USER2, 1, 100),
+                Error::<Test>::BelowMinimumBalance
+            );
+        })
+    }
+    #[test]
+    fn token_metadata_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            let uri = b"ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_vec();
+            assert_ok!(TokenModule::create_token(Origin::signed(USER1), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 0, None));
+            assert_noop!(
+                TokenModule::update_metadata(Origin::signed(USER2), 1, None, None, Some(uri.clone())),
+                "Only the token admin can manage the token"
+            );
+            assert_noop!(
+                TokenModule::update_metadata(Origin::signed(USER1), 1, None, None, Some(vec![0; 257])),
+                "The metadata is too long"
+            );
+            assert_ok!(TokenModule::update_metadata(Origin::signed(USER1), 1, None, None, Some(uri.clone())));
+            assert_eq!(TokenModule::token_metadata(1), uri);
+            assert_ok!(TokenModule::update_metadata(Origin::signed(USER1), 1, None, None, Some(vec![])));
+            assert_eq!(TokenModule::token_metadata(1), Vec::<u8>::new());
+        })
+    }
+    #[test]
+    fn update_metadata_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            let uri = b"ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_vec();
+            assert_ok!(TokenModule::create_token(Origin::signed(USER1), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 0, None));
+            assert_noop!(
+                TokenModule::update_metadata(Origin::signed(USER1), 1, Some(vec![]), None, None),
+                "The token name is empty"
+            );
 
-    const TOKEN_NAME: &[u8; 4] = b"DOOM";
-    const TOKEN_SHORT_NAME: &[u8; 1] = b"T";
-    const TOKEN_LONG_NAME: &[u8; 34] = b"nobody_really_want_such_long_token";
-    const USER1: u64 = 1;
-    const USER2: u64 = 2;
-    const TOKEN_ID: u32 = 0;
+            assert_ok!(TokenModule::update_metadata(Origin::signed(USER1), 1, Some(b"USD Coin v2".to_vec()), Some(8), Some(uri.clone())));
+            assert_eq!(TokenModule::token_name(1), b"USD Coin v2".to_vec());
+            assert_eq!(TokenModule::display_decimals(1), 8);
+            // the bridge keeps converting with the decimals of the token
+            assert_eq!(TokenModule::token_map(1).decimals, 6);
+            assert_eq!(TokenModule::tokens()[1].decimals, 6);
+            assert_eq!(TokenModule::token_metadata(1), uri);
+            assert_eq!(TokenModule::symbol_by_token_id(1), Some(b"USDC".to_vec()));
 
-    pub struct ExtBuilder {
-        existential_deposit: u128,
+            assert_ok!(TokenModule::freeze_metadata(Origin::signed(USER1), 1));
+            assert_noop!(
+                TokenModule::update_metadata(Origin::signed(USER1), 1, None, Some(6), None),
+                "The token metadata is frozen"
+            );
+            assert_noop!(
+                TokenModule::update_metadata(Origin::signed(USER1), 1, None, None, Some(vec![])),
+                "The token metadata is frozen"
+            );
+        })
     }
+    #[test]
+    fn account_tokens_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::create_token(Origin::signed(USER1), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 500, None));
+            assert_ok!(TokenModule::create_token(Origin::signed(USER2), b"USDT".to_vec(), b"Tether".to_vec(), 6, 700, None));
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER1, 1000));
 
-    impl Default for ExtBuilder {
-        fn default() -> Self {
-            Self {
-                existential_deposit: 500,
-            }
-        }
+            assert_eq!(TokenModule::account_tokens(USER1), vec![(TOKEN_ID, 1000), (1, 500)]);
+            assert_eq!(TokenModule::account_tokens(USER2), vec![(2, 700)]);
+            assert_eq!(TokenModule::account_tokens(3), vec![]);
+        })
     }
+    #[test]
+    fn holders_are_tracked() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER1, 1000));
+            assert_ok!(TokenModule::transfer(Origin::signed(USER1), USER2, TOKEN_ID, 300));
+            assert_ok!(TokenModule::transfer(Origin::signed(USER1), 3, TOKEN_ID, 300));
+            assert_eq!(TokenModule::holder_count(TOKEN_ID), 3);
+            assert_eq!(TokenModule::holders(TOKEN_ID, 0, 10), vec![USER1, USER2, 3]);
+            assert_eq!(TokenModule::holders(TOKEN_ID, 1, 1), vec![USER2]);
 
-    impl ExtBuilder {
-        pub fn set_associated_consts(&self) {
-            EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = self.existential_deposit);
-        }
-        pub fn build(self) -> sp_io::TestExternalities {
-            self.set_associated_consts();
-            let mut storage = system::GenesisConfig::default()
-                .build_storage::<Test>()
-                .unwrap();
+            // the emptied account is replaced by the last holder
+            assert_ok!(TokenModule::transfer(Origin::signed(USER1), USER2, TOKEN_ID, 400));
+            assert_eq!(TokenModule::holder_count(TOKEN_ID), 2);
+            assert_eq!(TokenModule::holders(TOKEN_ID, 0, 10), vec![3, USER2]);
 
-            let _ = balances::GenesisConfig::<Test> {
-                balances: vec![(USER1, 100000), (USER2, 300000)],
-            }
-            .assimilate_storage(&mut storage);
-            let _ = GenesisConfig {
-                tokens: vec![Token {
-                    id: 0,
-                    decimals: 18,
-                    symbol: TOKEN_NAME.to_vec(),
-                }],
-            }
-            .assimilate_storage(&mut storage);
+            assert_ok!(TokenModule::burn(Origin::signed(3), TOKEN_ID, 300, None));
+            assert_eq!(TokenModule::holders(TOKEN_ID, 0, 10), vec![USER2]);
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER1, 1));
+            assert_eq!(TokenModule::holders(TOKEN_ID, 0, 10), vec![USER2, USER1]);
+        })
+    }
+    #[test]
+    fn destroy_token_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            type Balances = balances::Module<Test>;
+            assert_ok!(TokenModule::set_creation_deposit(Origin::ROOT, 1000));
+            assert_noop!(
+                TokenModule::create_token(Origin::signed(3), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 100, None),
+                "Not enough balance for the creation deposit"
+            );
+            assert_ok!(TokenModule::create_token(Origin::signed(USER1), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 100, None));
+            assert_eq!(Balances::reserved_balance(USER1), 1000);
+            assert_noop!(
+                TokenModule::destroy_token(Origin::signed(USER2), 1),
+                "Only the token admin can manage the token"
+            );
+            assert_noop!(TokenModule::destroy_token(Origin::signed(USER1), 1), "The token still has supply");
 
-            let ext = sp_io::TestExternalities::from(storage);
-            ext
-        }
+            assert_ok!(TokenModule::burn(Origin::signed(USER1), 1, 100, None));
+            assert_ok!(TokenModule::approve(Origin::signed(USER1), USER2, 1, 50));
+            TokenModule::set_lock(VESTING_ID, 1, &USER1, 10);
+            assert_eq!(TokenModule::token_accounts(1), vec![USER1]);
+            assert_ok!(TokenModule::destroy_token(Origin::signed(USER1), 1));
+            assert_eq!(TokenModule::allowance_of((1, USER1), USER2), 0);
+            assert!(TokenModule::locks((1, USER1)).is_empty());
+            assert!(TokenModule::token_accounts(1).is_empty());
+            assert_noop!(TokenModule::_mint(1, USER2, 10), Error::<Test>::TokenNotFound);
+            assert_eq!(Balances::reserved_balance(USER1), 0);
+            assert_eq!(TokenModule::token_id_by_symbol(b"USDC".to_vec()), None);
+            assert_eq!(TokenModule::token_admin(1), None);
+            assert_eq!(TokenModule::tokens().len(), 1);
+            assert_eq!(TokenModule::check_token_exists(1), Err(Error::<Test>::TokenNotFound));
+
+            // the symbol is free again, under a new id
+            assert_ok!(TokenModule::create_token(Origin::signed(USER2), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 0, None));
+            assert_eq!(TokenModule::token_id_by_symbol(b"USDC".to_vec()), Some(2));
+            assert_ok!(TokenModule::force_destroy_token(Origin::ROOT, 2));
+        })
     }
+    #[test]
+    fn token_ownership_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::create_token(Origin::signed(USER1), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 0, None));
+            assert_noop!(
+                TokenModule::transfer_ownership(Origin::signed(USER2), 1, USER2),
+                "Only the token admin can manage the token"
+            );
+            assert_ok!(TokenModule::transfer_ownership(Origin::signed(USER1), 1, USER2));
+            assert_eq!(TokenModule::token_admin(1), Some(USER2));
+            assert_noop!(
+                TokenModule::pause_token(Origin::signed(USER1), 1),
+                "Only the token admin can manage the token"
+            );
 
+            assert_ok!(TokenModule::renounce_ownership(Origin::signed(USER2), 1));
+            assert_eq!(TokenModule::token_admin(1), None);
+            assert_noop!(
+                TokenModule::pause_token(Origin::signed(USER2), 1),
+                "Only the token admin can manage the token"
+            );
+        })
+    }
     #[test]
-    fn new_token_mint_works() {
+    fn soulbound_token_works() {
         ExtBuilder::default().build().execute_with(|| {
-            assert_ok!(TokenModule::check_token_exist(
-                &TokenModule::token_map(TOKEN_ID).symbol
-            ));
-            assert_ok!(TokenModule::_mint(TOKEN_ID, USER2, 1000));
-            assert_eq!(TokenModule::balance_of((TOKEN_ID, USER2)), 1000);
-            assert_eq!(TokenModule::total_supply(TOKEN_ID), 1000);
+            assert_ok!(TokenModule::create_token(Origin::signed(USER1), b"REP".to_vec(), b"Reputation".to_vec(), 0, 0, None));
+            assert_ok!(TokenModule::set_soulbound(Origin::signed(USER1), 1, true));
+            assert_ok!(TokenModule::grant_role(Origin::signed(USER1), 1, TokenRole::Minter, USER1));
+
+            assert_ok!(TokenModule::mint(Origin::signed(USER1), USER2, 1, 100));
+            assert_noop!(
+                TokenModule::transfer(Origin::signed(USER2), USER1, 1, 50),
+                Error::<Test>::NotTransferable
+            );
+            assert_ok!(TokenModule::approve(Origin::signed(USER2), USER1, 1, 50));
+            assert_noop!(
+                TokenModule::transfer_from(Origin::signed(USER1), USER2, USER1, 1, 50),
+                Error::<Test>::NotTransferable
+            );
+            assert_ok!(TokenModule::burn(Origin::signed(USER2), 1, 40, None));
+            assert_eq!(TokenModule::balance_of((1, USER2)), 60);
+        })
+    }
+    #[test]
+    fn whitelist_only_mode_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::create_token(Origin::signed(USER1), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 1000, None));
+            assert_noop!(
+                TokenModule::set_whitelist_only(Origin::signed(USER2), 1, true),
+                "Only the token admin can manage the token"
+            );
+            assert_ok!(TokenModule::set_whitelist_only(Origin::signed(USER1), 1, true));
+            assert_noop!(
+                TokenModule::transfer(Origin::signed(USER1), USER2, 1, 100),
+                Error::<Test>::SenderNotWhitelisted
+            );
+            assert_ok!(TokenModule::add_to_whitelist(Origin::signed(USER1), 1, USER1));
+            assert_noop!(
+                TokenModule::transfer(Origin::signed(USER1), USER2, 1, 100),
+                Error::<Test>::RecipientNotWhitelisted
+            );
+            assert_ok!(TokenModule::add_to_whitelist(Origin::signed(USER1), 1, USER2));
+            assert_ok!(TokenModule::transfer(Origin::signed(USER1), USER2, 1, 100));
+
+            assert_ok!(TokenModule::remove_from_whitelist(Origin::signed(USER1), 1, USER2));
+            assert_noop!(
+                TokenModule::transfer(Origin::signed(USER2), USER1, 1, 50),
+                Error::<Test>::SenderNotWhitelisted
+            );
+            assert_ok!(TokenModule::set_whitelist_only(Origin::signed(USER1), 1, false));
+            assert_ok!(TokenModule::transfer(Origin::signed(USER2), USER1, 1, 50));
         })
     }
+    #[test]
+    fn pause_token_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::create_token(Origin::signed(USER1), b"USDC".to_vec(), b"USD Coin".to_vec(), 6, 1000, None));
+            assert_noop!(
+                TokenModule::pause_token(Origin::signed(USER2), 1),
+                "Only the token admin can manage the token"
+            );
+            assert_ok!(TokenModule::pause_token(Origin::signed(USER1), 1));
+            assert_noop!(
+                TokenModule::transfer(Origin::signed(USER1), USER2, 1, 100),
+                Error::<Test>::TokenPaused
+            );
+            // governance of the paused token still works
+            assert_ok!(TokenModule::grant_role(Origin::signed(USER1), 1, TokenRole::Minter, USER2));
 
+            assert_ok!(TokenModule::unpause_token(Origin::signed(USER1), 1));
+            assert_ok!(TokenModule::transfer(Origin::signed(USER1), USER2, 1, 100));
+            assert_eq!(TokenModule::balance_of((1, USER2)), 100);
+        })
+    }
     #[test]
     fn new_token_mint_and_burn_works() {
         ExtBuilder::default().build().execute_with(|| {
-            assert_ok!(TokenModule::check_token_exist(
-                &TokenModule::token_map(TOKEN_ID).symbol
-            ));
+            assert_ok!(TokenModule::check_token_exists(TOKEN_ID));
             assert_ok!(TokenModule::_mint(TOKEN_ID, USER2, 1000));
             assert_eq!(TokenModule::balance_of((TOKEN_ID, USER2)), 1000);
 
@@ -420,6 +2613,88 @@ mod tests {
         })
     }
 
+    #[test]
+    fn zero_mint_fails() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_eq!(TokenModule::_mint(TOKEN_ID, USER1, 0), Err(Error::<Test>::ZeroAmount));
+        })
+    }
+
+    #[test]
+    fn mint_overflow_fails() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER1, Balance::max_value()));
+            // the balance overflows first for the same account, the total supply for a new one
+            assert_eq!(TokenModule::_mint(TOKEN_ID, USER1, 1), Err(Error::<Test>::Overflow));
+            assert_eq!(TokenModule::_mint(TOKEN_ID, USER2, 1), Err(Error::<Test>::Overflow));
+        })
+    }
+
+    #[test]
+    fn transfer_without_balance_fails() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER1, 1000));
+            assert_noop!(
+                TokenModule::transfer(Origin::signed(USER2), USER1, TOKEN_ID, 1),
+                Error::<Test>::InsufficientBalance
+            );
+        })
+    }
+
+    #[test]
+    fn burn_without_balance_fails() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_eq!(TokenModule::_burn(TOKEN_ID, USER2, 1), Err(Error::<Test>::InsufficientBalance));
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER1, 1000));
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER2, 10));
+            assert_eq!(TokenModule::_burn(TOKEN_ID, USER2, 11), Err(Error::<Test>::InsufficientBalance));
+            assert_noop!(TokenModule::burn(Origin::signed(USER2), TOKEN_ID, 0, None), Error::<Test>::ZeroAmount);
+        })
+    }
+
+    #[test]
+    fn burn_of_locked_funds_fails() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER1, 1000));
+            assert_ok!(TokenModule::lock(TOKEN_ID, USER1, 100));
+            assert_eq!(TokenModule::_burn(TOKEN_ID, USER1, 1000), Err(Error::<Test>::LockedFunds));
+        })
+    }
+
+    #[test]
+    fn unlock_above_locked_fails() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER1, 1000));
+            assert_ok!(TokenModule::lock(TOKEN_ID, USER1, 100));
+            assert_eq!(TokenModule::unlock(TOKEN_ID, &USER1, 101), Err(Error::<Test>::InsufficientLocked));
+        })
+    }
+
+    #[test]
+    fn reserve_without_balance_fails() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_eq!(TokenModule::reserve(TOKEN_ID, &USER2, 1), Err(Error::<Test>::InsufficientBalance));
+        })
+    }
+
+    #[test]
+    fn mint_of_unknown_token_fails() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_noop!(
+                TokenModule::mint(Origin::signed(USER1), USER2, 7, 1),
+                Error::<Test>::TokenNotFound
+            );
+        })
+    }
+
+    #[test]
+    fn self_transfer_keeps_balance() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER1, 1000));
+            assert_ok!(TokenModule::transfer(Origin::signed(USER1), USER1, TOKEN_ID, 1000));
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, USER1)), 1000);
+        })
+    }
     #[test]
     fn token_transfer_works() {
         ExtBuilder::default().build().execute_with(|| {
@@ -437,6 +2712,76 @@ mod tests {
         })
     }
     #[test]
+    fn operator_transfer_from_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER2, 1000));
+            assert_noop!(
+                TokenModule::set_operator(Origin::signed(USER2), USER2, true),
+                "The owner can't be its own operator"
+            );
+            assert_noop!(
+                TokenModule::transfer_from(Origin::signed(USER1), USER2, USER1, TOKEN_ID, 300),
+                "Underflow in calculating allowance"
+            );
+
+            assert_ok!(TokenModule::set_operator(Origin::signed(USER2), USER1, true));
+            assert!(TokenModule::is_operator(USER2, USER1));
+            assert_ok!(TokenModule::transfer_from(Origin::signed(USER1), USER2, USER1, TOKEN_ID, 300));
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, USER2)), 700);
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, USER1)), 300);
+            assert_eq!(TokenModule::allowance_of((TOKEN_ID, USER2), USER1), 0);
+
+            assert_ok!(TokenModule::set_operator(Origin::signed(USER2), USER1, false));
+            assert!(!TokenModule::is_operator(USER2, USER1));
+            assert_noop!(
+                TokenModule::transfer_from(Origin::signed(USER1), USER2, USER1, TOKEN_ID, 300),
+                "Underflow in calculating allowance"
+            );
+        })
+    }
+    #[test]
+    fn token_transfer_from_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER2, 1000));
+
+            assert_ok!(TokenModule::approve(Origin::signed(USER2), USER1, TOKEN_ID, 400));
+            assert_eq!(TokenModule::allowance_of((TOKEN_ID, USER2), USER1), 400);
+            assert_noop!(
+                TokenModule::transfer_from(Origin::signed(USER1), USER2, USER1, TOKEN_ID, 500),
+                "Underflow in calculating allowance"
+            );
+            assert_ok!(TokenModule::transfer_from(Origin::signed(USER1), USER2, USER1, TOKEN_ID, 300));
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, USER2)), 700);
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, USER1)), 300);
+            assert_eq!(TokenModule::allowance_of((TOKEN_ID, USER2), USER1), 100);
+
+            // allowance is not spent by the owner itself
+            assert_noop!(
+                TokenModule::transfer_from(Origin::signed(USER2), USER2, USER1, TOKEN_ID, 100),
+                "Underflow in calculating allowance"
+            );
+        })
+    }
+    #[test]
+    fn allowance_adjustment_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::increase_allowance(Origin::signed(USER2), USER1, TOKEN_ID, 300));
+            assert_ok!(TokenModule::increase_allowance(Origin::signed(USER2), USER1, TOKEN_ID, 200));
+            assert_eq!(TokenModule::allowance_of((TOKEN_ID, USER2), USER1), 500);
+            assert_noop!(
+                TokenModule::increase_allowance(Origin::signed(USER2), USER1, TOKEN_ID, u128::max_value()),
+                "Overflow increasing allowance"
+            );
+
+            assert_ok!(TokenModule::decrease_allowance(Origin::signed(USER2), USER1, TOKEN_ID, 100));
+            assert_eq!(TokenModule::allowance_of((TOKEN_ID, USER2), USER1), 400);
+            assert_noop!(
+                TokenModule::decrease_allowance(Origin::signed(USER2), USER1, TOKEN_ID, 500),
+                "Allowance is below the decreased amount"
+            );
+        })
+    }
+    #[test]
     fn token_lock_works() {
         ExtBuilder::default().build().execute_with(|| {
             assert_ok!(TokenModule::_mint(TOKEN_ID, USER2, 1000));
@@ -444,6 +2789,19 @@ mod tests {
             assert_eq!(TokenModule::balance_of((TOKEN_ID, USER2)), 1000);
             assert_ok!(TokenModule::lock(TOKEN_ID, USER2, 400));
             assert_eq!(TokenModule::locked((TOKEN_ID, USER2)), 400);
+            assert_ok!(TokenModule::lock(TOKEN_ID, USER2, 500));
+            assert_eq!(TokenModule::locked((TOKEN_ID, USER2)), 900);
+            assert_noop!(
+                TokenModule::lock(TOKEN_ID, USER2, 200),
+                Error::<Test>::InsufficientBalance
+            );
+
+            // the lock comes on top of the named locks
+            TokenModule::set_lock(VESTING_ID, TOKEN_ID, &USER2, 50);
+            assert_eq!(TokenModule::usable_balance(TOKEN_ID, &USER2), 50);
+            assert_noop!(TokenModule::lock(TOKEN_ID, USER2, 60), Error::<Test>::LockedFunds);
+            assert_ok!(TokenModule::lock(TOKEN_ID, USER2, 50));
+            assert_eq!(TokenModule::usable_balance(TOKEN_ID, &USER2), 0);
         })
     }
 
@@ -477,8 +2835,50 @@ mod tests {
             assert_eq!(TokenModule::locked((TOKEN_ID, USER2)), 0);
             assert_noop!(
                 TokenModule::transfer(Origin::signed(USER2), USER1, TOKEN_ID, 1300),
-                "User does not have enough tokens"
+                Error::<Test>::InsufficientBalance
+            );
+        })
+    }
+    #[test]
+    fn holder_burn_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER2, 1000));
+            assert_ok!(TokenModule::lock(TOKEN_ID, USER2, 600));
+
+            assert_noop!(
+                TokenModule::burn(Origin::signed(USER2), TOKEN_ID, 100, Some(vec![0; 129])),
+                Error::<Test>::MemoTooLong
+            );
+            assert_noop!(
+                TokenModule::burn(Origin::signed(USER2), TOKEN_ID, 500, None),
+                Error::<Test>::LockedFunds
+            );
+            assert_ok!(TokenModule::burn(Origin::signed(USER2), TOKEN_ID, 300, Some(b"redeem".to_vec())));
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, USER2)), 700);
+            assert_eq!(TokenModule::total_supply(TOKEN_ID), 700);
+        })
+    }
+    #[test]
+    fn burn_from_with_reason_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(TokenModule::_mint(TOKEN_ID, USER2, 1000));
+            TokenModule::_grant_role(TOKEN_ID, TokenRole::Burner, &USER1);
+
+            assert_noop!(
+                TokenModule::burn_from(Origin::signed(USER1), USER2, TOKEN_ID, 100, Some(BurnReason::Penalty), Some(vec![0; 129])),
+                Error::<Test>::MemoTooLong
             );
+            assert_ok!(TokenModule::burn_from(
+                Origin::signed(USER1),
+                USER2,
+                TOKEN_ID,
+                100,
+                Some(BurnReason::Penalty),
+                Some(b"late payment".to_vec())
+            ));
+            assert_ok!(TokenModule::burn_as(&USER1, TOKEN_ID, USER2, 100, None, None));
+            assert_eq!(TokenModule::balance_of((TOKEN_ID, USER2)), 800);
+            assert_eq!(TokenModule::total_supply(TOKEN_ID), 800);
         })
     }
     #[test]
@@ -1,5 +1,6 @@
 use codec::{Decode, Encode};
-use sp_core::H160;
+use sp_core::{H160, H256};
+use sp_runtime::{Perbill, RuntimeDebug};
 use sp_std::prelude::Vec;
 
 #[cfg(feature = "std")]
@@ -69,6 +70,8 @@ pub enum Action<AccountId, Balance, Timeout> {
 
 //token factory
 pub type TokenId = u32;
+pub type SnapshotId = u32;
+pub type DistributionId = u32;
 
 #[derive(Encode, Decode, Default, Clone, PartialEq)]
 #[cfg_attr(feature = "std", derive(Deserialize, Serialize, Debug))]
@@ -78,8 +81,47 @@ pub struct Token {
     pub symbol: Vec<u8>,
 }
 
+// permissions granted by the token admin
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum TokenRole {
+    Minter,
+    Burner,
+}
+
+// classifies supply reductions for off-chain accounting
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum BurnReason {
+    Redemption,
+    BridgeExit,
+    Penalty,
+}
+
+// payment in the source token shared by the holders of the token at the snapshot
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Distribution<Balance> {
+    pub token: TokenId,
+    pub snapshot: SnapshotId,
+    pub source_token: TokenId,
+    pub amount: Balance,
+    pub supply: Balance,
+}
+
+// tokens locked at mint and unlocked linearly after the starting block
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct VestingSchedule<Balance, BlockNumber> {
+    pub locked: Balance,
+    pub per_block: Balance,
+    pub starting_block: BlockNumber,
+}
+
 //bridge
-#[derive(Encode, Decode, Clone, PartialEq)]
+pub type EthAddress = H160;
+// network the token is bridged with, 0 is the ethereum mainnet
+pub type ChainId = u32;
+// token id of the ERC-721 asset (uint256)
+pub type NftId = H256;
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub struct Limits<Balance> {
     pub max_tx_value: Balance,
@@ -92,7 +134,21 @@ pub struct Limits<Balance> {
 // bridge types
 #[derive(Encode, Decode, Clone)]
 #[cfg_attr(feature = "std", derive(Debug))]
-pub struct BridgeTransfer<Hash> {
+pub struct BridgeTransfer<AccountId, Hash, BlockNumber> {
+    pub transfer_id: ProposalId,
+    pub message_id: Hash,
+    pub status: ProposalStatus,
+    pub votes: MemberId,
+    pub kind: Kind,
+    pub chain_id: ChainId,
+    // account which opened the proposal and the block it was opened at
+    pub proposer: AccountId,
+    pub created_at: BlockNumber,
+}
+
+// layout of the proposals stored before the status, read once by the migration
+#[derive(Encode, Decode, Clone)]
+pub struct LegacyBridgeTransfer<Hash> {
     pub transfer_id: ProposalId,
     pub message_id: Hash,
     pub open: bool,
@@ -100,6 +156,45 @@ pub struct BridgeTransfer<Hash> {
     pub kind: Kind,
 }
 
+// proposal as seen by the relayers: direction of the message,
+// votes of the current round and the block it expires at
+#[derive(Encode, Decode, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ProposalView<Hash, BlockNumber> {
+    pub transfer_id: ProposalId,
+    pub message_id: Hash,
+    pub kind: Kind,
+    pub action: Option<Status>,
+    pub votes: MemberId,
+    pub deadline: Option<BlockNumber>,
+}
+
+// bridge totals of the token, amounts are in substrate decimals
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct BridgeStats<Balance> {
+    pub minted: Balance,
+    pub burned: Balance,
+    pub executed: Count,
+    pub expired: Count,
+    pub rejected: Count,
+}
+
+// lifecycle of the bridge proposal, only pending proposals accept votes
+#[derive(Encode, Decode, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum ProposalStatus {
+    Pending,
+    // quorum is reached, execution is scheduled or awaits ethereum confirmation
+    Approved,
+    Executed,
+    // canceled by validators or by the handler of the message
+    Rejected,
+    Expired,
+    // canceled by root
+    Cancelled,
+}
+
 #[derive(Encode, Decode, Clone, PartialEq)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub enum Status {
@@ -114,6 +209,11 @@ pub enum Status {
     Approved,
     Canceled,
     Confirmed,
+    PauseDeposits,
+    ResumeDeposits,
+    PauseWithdrawals,
+    ResumeWithdrawals,
+    Challenged,
 }
 
 #[derive(Encode, Decode, Clone, PartialEq)]
@@ -123,6 +223,92 @@ pub enum Kind {
     Limits,
     Validator,
     Bridge,
+    Parameter,
+    Treasury,
+    Nft,
+    Generic,
+    Batch,
+}
+
+#[derive(Encode, Decode, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum Parameter<Balance> {
+    EmptyParameter,
+    AccountDailyQuota(Balance),
+    Fee(BridgeFee<Balance>),
+    RelayerCompensation(Perbill),
+    DisputePeriod(u32),
+    LargeTransferThreshold(Balance),
+    ValidatorReward(Perbill),
+    FollowAuthorities(bool),
+    EthSigner(EthAddress, bool),
+    EthCheckpoint(H256),
+    EthBridgeContract(EthAddress),
+    EthBlockNumber(u64),
+    EthConfirmations(u64),
+    TokenLimits(TokenId, Limits<Balance>),
+    TokenFee(TokenId, BridgeFee<Balance>),
+    EthToken(EthAddress, Vec<u8>, u16),
+    RemoveEthToken(EthAddress),
+    EthDecimals(TokenId, u16),
+    SupplyCap(TokenId, Balance),
+    // network, enabled or removed
+    TokenNetwork(TokenId, ChainId, bool),
+    QuorumThreshold(Perbill),
+    InsuranceShare(Perbill),
+    TokenFeeShares(TokenId, FeeShares),
+    FastLaneThreshold(Balance),
+    TokenConfig(TokenId, BridgeTokenConfig<Balance>),
+    VoteWeightCap(Option<Balance>),
+}
+
+// used as extrinsic argument
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum BridgeFee<Balance> {
+    Flat(Balance),
+    Proportional(Perbill),
+}
+
+// fee of token transfers, paid by the sender out of the transferred amount
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum TransferFee<Balance> {
+    Flat(Balance),
+    Proportional(Perbill),
+}
+
+// split of the collected fee, insurance takes its part of the fee left after validators,
+// the treasury keeps the rest
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct FeeShares {
+    pub validators: Perbill,
+    pub insurance: Perbill,
+}
+
+// bridge policy of one token, voted as a whole
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct BridgeTokenConfig<Balance> {
+    pub fee: BridgeFee<Balance>,
+    pub limits: Limits<Balance>,
+    pub paused: bool,
+    pub decimals: u16,
+}
+
+// validator action relayed without fees, signed by the validator key
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum ValidatorAction<AccountId, Hash, Balance> {
+    // (transfer_id, approve)
+    Vote(ProposalId, bool),
+    // (message_id, from, to, eth_token, amount, eth_block)
+    Deposit(Hash, EthAddress, AccountId, EthAddress, Balance, u64),
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ValidatorPayload<AccountId, Hash, Balance> {
+    pub validator: AccountId,
+    pub action: ValidatorAction<AccountId, Hash, Balance>,
+    // genesis hash of the chain and the next nonce of the validator
+    pub genesis_hash: Hash,
+    pub nonce: u32,
 }
 
 #[derive(Encode, Decode, Clone)]
@@ -130,13 +316,44 @@ pub enum Kind {
 pub struct TransferMessage<AccountId, Hash, Balance> {
     pub message_id: Hash,
     pub token: TokenId,
-    pub eth_address: H160,
+    pub eth_address: EthAddress,
     pub substrate_address: AccountId,
     pub amount: Balance,
     pub status: Status,
     pub action: Status,
 }
 
+#[derive(Encode, Decode, Clone)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct NftMessage<AccountId, Hash> {
+    pub message_id: Hash,
+    pub collection: EthAddress,
+    pub token_ref: NftId,
+    pub eth_address: EthAddress,
+    pub substrate_address: AccountId,
+    pub status: Status,
+    pub action: Status,
+}
+
+#[derive(Encode, Decode, Clone)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct GenericMessage<Hash> {
+    pub message_id: Hash,
+    pub target: Vec<u8>,
+    pub payload: Vec<u8>,
+    pub status: Status,
+}
+
+// deposits approved together: (message_id, from, to, amount)
+#[derive(Encode, Decode, Clone)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct BatchMessage<AccountId, Hash, Balance> {
+    pub id: Hash,
+    pub token: TokenId,
+    pub transfers: Vec<(Hash, EthAddress, AccountId, Balance)>,
+    pub status: Status,
+}
+
 #[derive(Encode, Decode, Clone)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub struct LimitMessage<Hash, Balance> {
@@ -145,6 +362,24 @@ pub struct LimitMessage<Hash, Balance> {
     pub status: Status,
 }
 
+#[derive(Encode, Decode, Clone)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ParameterMessage<Hash, Balance> {
+    pub id: Hash,
+    pub parameter: Parameter<Balance>,
+    pub status: Status,
+}
+
+#[derive(Encode, Decode, Clone)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct TreasuryMessage<AccountId, Hash, Balance> {
+    pub id: Hash,
+    pub token: TokenId,
+    pub account: AccountId,
+    pub amount: Balance,
+    pub status: Status,
+}
+
 #[derive(Encode, Decode, Clone)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub struct BridgeMessage<AccountId, Hash> {
@@ -174,7 +409,7 @@ where
         TransferMessage {
             message_id: H::default(),
             token: TokenId::default(),
-            eth_address: H160::default(),
+            eth_address: EthAddress::default(),
             substrate_address: A::default(),
             amount: B::default(),
             status: Status::Withdraw,
@@ -183,6 +418,52 @@ where
     }
 }
 
+impl<A, H> Default for NftMessage<A, H>
+where
+    A: Default,
+    H: Default,
+{
+    fn default() -> Self {
+        NftMessage {
+            message_id: H::default(),
+            collection: EthAddress::default(),
+            token_ref: NftId::default(),
+            eth_address: EthAddress::default(),
+            substrate_address: A::default(),
+            status: Status::Withdraw,
+            action: Status::Withdraw,
+        }
+    }
+}
+
+impl<H> Default for GenericMessage<H>
+where
+    H: Default,
+{
+    fn default() -> Self {
+        GenericMessage {
+            message_id: H::default(),
+            target: Vec::default(),
+            payload: Vec::default(),
+            status: Status::Revoked,
+        }
+    }
+}
+
+impl<A, H, B> Default for BatchMessage<A, H, B>
+where
+    H: Default,
+{
+    fn default() -> Self {
+        BatchMessage {
+            id: H::default(),
+            token: TokenId::default(),
+            transfers: Vec::default(),
+            status: Status::Revoked,
+        }
+    }
+}
+
 impl<H, B> Default for LimitMessage<H, B>
 where
     H: Default,
@@ -197,6 +478,45 @@ where
     }
 }
 
+impl<H, B> Default for ParameterMessage<H, B>
+where
+    H: Default,
+{
+    fn default() -> Self {
+        ParameterMessage {
+            id: H::default(),
+            parameter: Parameter::EmptyParameter,
+            status: Status::Revoked,
+        }
+    }
+}
+
+impl<A, H, B> Default for TreasuryMessage<A, H, B>
+where
+    A: Default,
+    H: Default,
+    B: Default,
+{
+    fn default() -> Self {
+        TreasuryMessage {
+            id: H::default(),
+            token: TokenId::default(),
+            account: A::default(),
+            amount: B::default(),
+            status: Status::Revoked,
+        }
+    }
+}
+
+impl<B> Default for BridgeFee<B>
+where
+    B: Default,
+{
+    fn default() -> Self {
+        BridgeFee::Flat(B::default())
+    }
+}
+
 impl<A, H> Default for BridgeMessage<A, H>
 where
     A: Default,
@@ -228,17 +548,22 @@ where
     }
 }
 
-impl<H> Default for BridgeTransfer<H>
+impl<A, H, B> Default for BridgeTransfer<A, H, B>
 where
+    A: Default,
     H: Default,
+    B: Default,
 {
     fn default() -> Self {
         BridgeTransfer {
             transfer_id: ProposalId::default(),
             message_id: H::default(),
-            open: true,
+            status: ProposalStatus::Pending,
             votes: MemberId::default(),
             kind: Kind::Transfer,
+            chain_id: ChainId::default(),
+            proposer: A::default(),
+            created_at: B::default(),
         }
     }
 }
@@ -129,6 +129,6 @@ pub fn config_endowed(
 			id: 0,
 			decimals: 18,
 			symbol: Vec::from("TOKEN"),
-		}] }),
+		}], max_supplies: vec![], balances: vec![] }),
     }
 }